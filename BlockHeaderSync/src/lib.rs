@@ -2,13 +2,37 @@
 #![no_std]
 
 use header::*;
+use header::chain_config::*;
+use header::header_record::*;
 use header::peer_config::*;
+use header::vbft_block_info::*;
 
 use util::*;
 
-use elrond_wasm::imports;
+use pause::*;
+use relayer_rewards::*;
+
+use elrond_wasm::{derive_imports, imports, only_owner};
 
 imports!();
+derive_imports!();
+
+// bounds the gas cost pruning adds to syncBlockHeader
+const MAX_PRUNED_HEADERS_PER_SYNC: usize = 10;
+
+// bounds the size of the getHeadersInRange result
+const MAX_HEADERS_PER_RANGE_QUERY: u32 = 100;
+
+// domain separation of the leaves and inner nodes of Poly's cross state merkle tree
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+/// How the headers of a chain are verified
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum VerifierType {
+    PolyVbft,       // signed by the Poly consensus, which may change through key headers
+    StaticMultisig, // signed by a threshold of keys set by the owner
+}
 
 #[elrond_wasm_derive::contract(BlockHeaderSyncImpl)]
 pub trait BlockHeaderSync {
@@ -17,17 +41,137 @@ pub trait BlockHeaderSync {
         
     }
 
-    // endpoints
+    // endpoints - owner-only
+
+    #[endpoint(setSyncBlockHeaderReward)]
+    fn set_sync_block_header_reward_endpoint(
+        &self,
+        token_identifier: TokenIdentifier,
+        reward: BigUint,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_sync_block_header_reward_token(&token_identifier);
+        self.set_sync_block_header_reward(&reward);
+
+        Ok(())
+    }
+
+    /// The CrossChainManagement contract, which keeps the relayer registry and mirrors its approved relayers here
+    #[endpoint(setRelayerRegistryAddress)]
+    fn set_relayer_registry_address_endpoint(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_relayer_registry_address(&address);
+
+        Ok(())
+    }
+
+    #[endpoint(unpauseHeaderSync)]
+    fn unpause_header_sync(&self) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_header_sync_paused(false);
+
+        Ok(())
+    }
+
+    #[endpoint(unpauseChain)]
+    fn unpause_chain(&self, chain_id: u64) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_chain_paused(chain_id, false);
+
+        Ok(())
+    }
+
+    /// How far ahead of the Elrond block timestamp a header's timestamp may be, in seconds
+    #[endpoint(setMaxHeaderFutureTolerance)]
+    fn set_max_header_future_tolerance_endpoint(&self, tolerance: u64) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_max_header_future_tolerance(tolerance);
+
+        Ok(())
+    }
+
+    /// Once the latest header of a chain is older than this, in seconds, the chain is considered stale
+    #[endpoint(setMaxHeaderAge)]
+    fn set_max_header_age_endpoint(&self, chain_id: u64, max_age: u64) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(max_age > 0, "Max header age must be more than 0");
+
+        self.set_max_header_age(chain_id, max_age);
+
+        Ok(())
+    }
+
+    /// Consensus changes are only accepted in headers at multiples of the epoch length
+    #[endpoint(setEpochLength)]
+    fn set_epoch_length_endpoint(&self, chain_id: u64, epoch_length: u32) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(epoch_length > 0, "Epoch length must be more than 0");
+
+        self.set_epoch_length(chain_id, epoch_length);
+
+        Ok(())
+    }
+
+    /// The VRF proofs of Poly VBFT headers can't be verified, so consensus changes are rejected by default.
+    /// Once accepted, consensus changes of _chain_id_ are trusted on the signatures of the previous consensus alone.
+    #[endpoint(setAcceptUnverifiedVrf)]
+    fn set_accept_unverified_vrf_endpoint(&self, chain_id: u64, accept: bool) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_accept_unverified_vrf(chain_id, accept);
+
+        Ok(())
+    }
+
+    /// Headers older than _retention_blocks_ are pruned, except for key headers and headers at the start of an epoch.
+    /// 0 means all headers are kept.
+    #[endpoint(setHeaderRetention)]
+    fn set_header_retention_endpoint(&self, chain_id: u64, retention_blocks: u32) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_header_retention(chain_id, retention_blocks);
+
+        Ok(())
+    }
 
+    /// Sets how the headers of _chain_id_ are verified. Chains are verified as Poly VBFT by default.
+    /// Can only be changed before the chain's genesis header is synced.
+    #[endpoint(registerChain)]
+    fn register_chain(&self, chain_id: u64, verifier_type: VerifierType) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(
+            self.is_empty_genesis_header(chain_id),
+            "Chain already has a genesis header"
+        );
+
+        self.set_verifier_type(chain_id, verifier_type);
+
+        Ok(())
+    }
+
+    /// The genesis header sets the chain's initial consensus, which every later header is verified against,
+    /// so only the owner may sync it, after picking the chain's verifier through registerChain if needed.
     #[endpoint(syncGenesisHeader)]
     fn sync_genesis_header(&self, header: Header) -> SCResult<()> {
-        require!(self.is_empty_genesis_header(), "Genesis header already set!");
-        require!(!header.consensus_payload.is_some(), "Invalid genesis header!");
+        only_owner!(self, "only owner may call this function");
+        sc_try!(self.require_header_sync_not_paused(header.chain_id));
+        require!(self.is_empty_genesis_header(header.chain_id), "Genesis header already set!");
+
+        let header_hash = sc_try!(self.hash_header(&header));
 
         let sc_result = self.update_consensus_peer(&header);
         if sc_result.is_ok() {
-            self.set_genesis_header(&header);
-            self.store_header(&header);
+            self.set_genesis_header(header.chain_id, &header);
+            self.store_header(&header, &header_hash);
+
+            let mut tracked_chains = self.get_tracked_chains();
+            tracked_chains.push(header.chain_id);
+            self.set_tracked_chains(&tracked_chains);
             
             self.block_header_sync_event(&header);
         }
@@ -35,10 +179,164 @@ pub trait BlockHeaderSync {
         sc_result
     }
 
+    /// Replaces the key set of a StaticMultisig chain. Headers need valid signatures from at least _threshold_ of the keys.
+    #[endpoint(setMultisigKeys)]
+    fn set_multisig_keys_endpoint(
+        &self,
+        chain_id: u64,
+        threshold: u32,
+        #[var_args] keys: VarArgs<PublicKey>,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(
+            self.get_verifier_type_or_default(chain_id) == VerifierType::StaticMultisig,
+            "Chain is not verified by a static multisig"
+        );
+
+        let keys = keys.into_vec();
+        require!(
+            threshold > 0 && threshold as usize <= keys.len(),
+            "Multisig threshold must be between 1 and the number of keys"
+        );
+        for (i, key) in keys.iter().enumerate() {
+            require!(!keys[..i].contains(key), "Duplicate multisig key");
+        }
+
+        self.set_multisig_keys(chain_id, &keys);
+        self.set_multisig_threshold(chain_id, threshold);
+
+        Ok(())
+    }
+
+    /// Removes the stored headers from _from_height_ up to the current height, rolling back the consensus changes among them,
+    /// so the canonical headers can be synced again once the fork is resolved. Only allowed while the chain is frozen.
+    /// At most MAX_HEADERS_PER_RANGE_QUERY headers are removed at once, so longer ranges are rolled back from the top down.
+    #[endpoint(rollBackHeaders)]
+    fn roll_back_headers(&self, chain_id: u64, from_height: u32) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(self.is_chain_frozen(chain_id), "Chain is not frozen");
+
+        let genesis_height = self.get_genesis_header(chain_id).height;
+        let current_height = self.get_current_height(chain_id);
+        require!(
+            from_height > genesis_height && from_height <= current_height,
+            "Can only roll back synced headers above the genesis header"
+        );
+        require!(
+            current_height - from_height < MAX_HEADERS_PER_RANGE_QUERY,
+            "Height range too large"
+        );
+
+        for height in from_height..=current_height {
+            if !self.is_empty_header_hash_by_height(chain_id, height) {
+                let hash = self.get_header_hash_by_height(chain_id, height);
+
+                self.clear_header_record(chain_id, &hash);
+                self.clear_header_hash_by_height(chain_id, height);
+            }
+        }
+
+        let mut key_heights = self.get_key_height_list(chain_id);
+        while let Some(key_height) = key_heights.last().copied() {
+            if key_height < from_height {
+                break;
+            }
+
+            self.clear_consensus(chain_id, key_height);
+            key_heights.pop();
+        }
+        self.set_key_height_list(chain_id, &key_heights);
+
+        // the header below may have been pruned, the genesis header never is
+        let previous_height = from_height - 1;
+        let latest_timestamp = if !self.is_empty_header_hash_by_height(chain_id, previous_height) {
+            let hash = self.get_header_hash_by_height(chain_id, previous_height);
+            self.get_header_record(chain_id, &hash).timestamp
+        } else {
+            self.get_genesis_header(chain_id).timestamp
+        };
+
+        self.set_current_height(chain_id, previous_height);
+        self.set_latest_header_timestamp(chain_id, latest_timestamp as u64);
+
+        Ok(())
+    }
+
+    /// Unfreezes the chain and discards the recorded fork evidence.
+    /// If the stored headers from the fork on shouldn't be kept, rollBackHeaders has to be called before this.
+    #[endpoint(resolveFork)]
+    fn resolve_fork(&self, chain_id: u64) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(self.is_chain_frozen(chain_id), "Chain is not frozen");
+
+        for height in self.get_fork_heights(chain_id) {
+            self.clear_fork_evidence(chain_id, height);
+        }
+        self.clear_fork_heights(chain_id);
+        self.set_chain_frozen(chain_id, false);
+
+        Ok(())
+    }
+
+    // endpoints - relayer registry only
+
+    #[endpoint(setApprovedRelayer)]
+    fn set_approved_relayer(&self, relayer: Address, approved: bool) -> SCResult<()> {
+        require!(
+            !self.is_empty_relayer_registry_address()
+                && self.get_caller() == self.get_relayer_registry_address(),
+            "Only the relayer registry may approve relayers"
+        );
+
+        let mut approved_address_list = self.get_approved_address_list();
+
+        if approved {
+            if !approved_address_list.contains(&relayer) {
+                approved_address_list.push(relayer);
+
+                self.set_approved_address_list(&approved_address_list);
+            }
+        } else if let Some(index) = approved_address_list.iter().position(|addr| addr == &relayer) {
+            approved_address_list.remove(index);
+
+            self.set_approved_address_list(&approved_address_list);
+        }
+
+        Ok(())
+    }
+
+    // endpoints - guardian or owner
+
+    #[endpoint(pauseHeaderSync)]
+    fn pause_header_sync(&self) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_header_sync_paused(true);
+
+        Ok(())
+    }
+
+    #[endpoint(pauseChain)]
+    fn pause_chain(&self, chain_id: u64) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_chain_paused(chain_id, true);
+
+        Ok(())
+    }
+
+    // endpoints
+
     #[endpoint(syncBlockHeader)]
     fn sync_block_header(&self, header: Header) -> SCResult<()> {
-        
-        if self.is_empty_header_by_height(header.chain_id, header.height) {
+        sc_try!(self.require_header_sync_not_paused(header.chain_id));
+        require!(!self.is_chain_frozen(header.chain_id), "Chain is frozen");
+
+        let header_hash = sc_try!(self.hash_header(&header));
+
+        if self.is_empty_header_hash_by_height(header.chain_id, header.height) {
+            sc_try!(self.check_header_timestamp(&header));
+
             match self.verify_header(&header) {
                 Ok(()) => {},
                 Err(err) => return Err(err)
@@ -46,21 +344,194 @@ pub trait BlockHeaderSync {
 
             let sc_result = self.update_consensus_peer(&header);
             if sc_result.is_ok() {
-                self.store_header(&header);
+                self.store_header(&header, &header_hash);
+                self.add_header_to_prune_queue(&header);
+                self.prune_headers(header.chain_id, header.height);
+
                 self.block_header_sync_event(&header);
+
+                self.credit_header_sync_reward(&self.get_caller());
             }
 
             return sc_result;
         }
 
         // if block exists already, no sync needed
+        let stored_header_hash = self.get_header_hash_by_height(header.chain_id, header.height);
+        if header_hash == stored_header_hash {
+            return Ok(());
+        }
+
+        // a different header at the same height, signed by the same consensus, means the validators double-signed
+        sc_try!(self.verify_conflicting_header(&header));
+
+        self.record_fork(&stored_header_hash, &header, &header_hash);
+
         Ok(())
     }
 
     #[view(getHeaderByHeight)]
-    fn get_header_by_height_endpoint(&self, chain_id: u64, height: u32) -> Option<Header> {
-        if !self.is_empty_header_by_height(chain_id, height) {
-            Some(self.get_header_by_height(chain_id, height))
+    fn get_header_by_height_endpoint(&self, chain_id: u64, height: u32) -> Option<HeaderRecord> {
+        if !self.is_empty_header_hash_by_height(chain_id, height) {
+            let hash = self.get_header_hash_by_height(chain_id, height);
+
+            Some(self.get_header_record(chain_id, &hash))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Headers between _start_height_ and _end_height_, both inclusive. Heights without a stored header are skipped.
+    #[view(getHeadersInRange)]
+    fn get_headers_in_range(&self, chain_id: u64, start_height: u32, end_height: u32) -> SCResult<Vec<HeaderRecord>> {
+        require!(start_height <= end_height, "Invalid height range");
+        require!(
+            end_height - start_height < MAX_HEADERS_PER_RANGE_QUERY,
+            "Height range too large"
+        );
+
+        let mut headers = Vec::new();
+        for height in start_height..=end_height {
+            if let Some(record) = self.get_header_by_height_endpoint(chain_id, height) {
+                headers.push(record);
+            }
+        }
+
+        Ok(headers)
+    }
+
+    /// Only the hash and roots of the header, for callers that don't need the rest of the record
+    #[view(getHeaderRoots)]
+    fn get_header_roots(&self, chain_id: u64, height: u32) -> Option<HeaderRoots> {
+        self.get_header_by_height_endpoint(chain_id, height)
+            .map(|record| HeaderRoots::from_record(&record))
+    }
+
+    #[view(getGenesisHeader)]
+    fn get_genesis_header_endpoint(&self, chain_id: u64) -> Option<Header> {
+        if !self.is_empty_genesis_header(chain_id) {
+            Some(self.get_genesis_header(chain_id))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Called synchronously by the CrossChainManagement contract, before executing or releasing an inbound transaction.
+    /// Fails if the chain is frozen or stale.
+    #[endpoint(checkChainTrusted)]
+    fn check_chain_trusted(&self, chain_id: u64) -> SCResult<()> {
+        require!(!self.is_chain_frozen(chain_id), "Chain is frozen");
+        require!(!self.is_chain_stale(chain_id), "Chain headers are stale");
+
+        Ok(())
+    }
+
+    /// Called synchronously by the CrossChainManagement contract, which relies on it failing to reject inbound transactions.
+    /// Fails if the header is not synced, or if the chain is frozen or stale.
+    #[endpoint(getHeaderForInboundTx)]
+    fn get_header_for_inbound_tx(&self, chain_id: u64, height: u32) -> SCResult<HeaderRecord> {
+        sc_try!(self.check_chain_trusted(chain_id));
+
+        match self.get_header_by_height_endpoint(chain_id, height) {
+            Some(record) => Ok(record),
+            None => sc_error!("Header not synced")
+        }
+    }
+
+    /// Called synchronously by the CrossChainManagement contract, which relies on it failing to reject inbound transactions.
+    /// Fails like `getHeaderForInboundTx`, or if _audit_path_ doesn't prove _merkle_value_ against the header's cross state root.
+    #[endpoint(verifyInboundTx)]
+    fn verify_inbound_tx(
+        &self,
+        chain_id: u64,
+        height: u32,
+        merkle_value: BoxedBytes,
+        audit_path: BoxedBytes,
+    ) -> SCResult<()> {
+        let record = sc_try!(self.get_header_for_inbound_tx(chain_id, height));
+        let merkle_root = sc_try!(self.compute_merkle_root(merkle_value.as_slice(), audit_path.as_slice()));
+
+        require!(merkle_root == record.cross_state_root, "Invalid transaction proof");
+
+        Ok(())
+    }
+
+    /// A chain is stale if its latest synced header is older than the max header age set for it.
+    /// Chains without a max header age are never stale.
+    #[view(isChainStale)]
+    fn is_chain_stale(&self, chain_id: u64) -> bool {
+        if self.is_empty_max_header_age(chain_id) {
+            return false;
+        }
+
+        self.get_block_timestamp()
+            > self.get_latest_header_timestamp(chain_id) + self.get_max_header_age(chain_id)
+    }
+
+    /// The consensus peers set at _key_height_, as received in the header's chain config
+    #[view(getConsensusPeers)]
+    fn get_consensus_peers(&self, chain_id: u64, key_height: u32) -> Vec<PeerConfig> {
+        if !self.is_empty_chain_config(chain_id, key_height) {
+            self.get_chain_config(chain_id, key_height).peers
+        }
+        else {
+            Vec::new()
+        }
+    }
+
+    /// The consensus peers that were active at _height_, i.e. set by the latest key header at or below it
+    /// The index of the peer with _public_key_, compressed or not, in the consensus set at _height_. 0 if it isn't a peer.
+    #[view(getConsensusPeerIndex)]
+    fn get_consensus_peer_index_view(&self, chain_id: u64, height: u32, public_key: PublicKey) -> u32 {
+        self.get_consensus_peer_index(chain_id, height, &public_key.to_compressed_bytes())
+    }
+
+    #[view(getConsensusPeersAtHeight)]
+    fn get_consensus_peers_at_height(&self, chain_id: u64, height: u32) -> Vec<PeerConfig> {
+        match self.find_key_height_at(chain_id, height) {
+            Some(key_height) => self.get_consensus_peers(chain_id, key_height),
+            None => Vec::new()
+        }
+    }
+
+    #[view(getVerifierType)]
+    fn get_verifier_type_or_default(&self, chain_id: u64) -> VerifierType {
+        if !self.is_empty_verifier_type(chain_id) {
+            self.get_verifier_type(chain_id)
+        }
+        else {
+            VerifierType::PolyVbft
+        }
+    }
+
+    /// The keys allowed to sign the headers of _chain_id_ at _height_, whatever the chain's verifier
+    #[view(getVerifierKeys)]
+    fn get_verifier_keys(&self, chain_id: u64, height: u32) -> Vec<PublicKey> {
+        match self.get_verifier_type_or_default(chain_id) {
+            VerifierType::PolyVbft => self.get_consensus_peers_at_height(chain_id, height)
+                .iter()
+                .filter_map(|peer| self.peer_public_key(peer))
+                .collect(),
+            VerifierType::StaticMultisig => self.get_multisig_keys(chain_id)
+        }
+    }
+
+    #[view(getEpochLength)]
+    fn get_epoch_length_or_default(&self, chain_id: u64) -> u32 {
+        if !self.is_empty_epoch_length(chain_id) {
+            self.get_epoch_length(chain_id)
+        }
+        else {
+            POLYCHAIN_EPOCH_HEIGHT
+        }
+    }
+
+    #[view(getForkEvidence)]
+    fn get_fork_evidence_endpoint(&self, chain_id: u64, height: u32) -> Option<Header> {
+        if !self.is_empty_fork_evidence(chain_id, height) {
+            Some(self.get_fork_evidence(chain_id, height))
         }
         else {
             None
@@ -68,9 +539,9 @@ pub trait BlockHeaderSync {
     }
 
     #[view(getHeaderByHash)]
-    fn get_header_by_hash_endpoint(&self, chain_id: u64, hash: &H256) -> Option<Header> {
-        if !self.is_empty_header_by_hash(chain_id, hash) {
-            Some(self.get_header_by_hash(chain_id, hash))
+    fn get_header_by_hash_endpoint(&self, chain_id: u64, hash: &H256) -> Option<HeaderRecord> {
+        if !self.is_empty_header_record(chain_id, hash) {
+            Some(self.get_header_record(chain_id, hash))
         }
         else {
             None
@@ -79,72 +550,474 @@ pub trait BlockHeaderSync {
 
     #[endpoint(verifyHeader)]
     fn verify_header(&self, header: &Header) -> SCResult<()> {
-        let chain_id = header.chain_id;
-        let height = header.height;
+        match self.get_verifier_type_or_default(header.chain_id) {
+            VerifierType::PolyVbft => self.verify_poly_vbft_header(header),
+            VerifierType::StaticMultisig => self.verify_static_multisig_header(header)
+        }
+    }
 
-        let key_height = match self.find_key_height(chain_id, height) {
+    // private
+
+    /// Only the signatures matter as evidence of a double-sign, so the consensus payload is not checked.
+    /// The header may be below the latest key height, so it's checked against the consensus that signed that height,
+    /// set by the latest key header strictly below it.
+    fn verify_conflicting_header(&self, header: &Header) -> SCResult<()> {
+        match self.get_verifier_type_or_default(header.chain_id) {
+            VerifierType::PolyVbft => {
+                let key_height = match self.find_key_height_at(header.chain_id, header.height - 1) {
+                    Some(k) => k,
+                    None => return sc_error!("Couldn't find key height!")
+                };
+
+                self.verify_poly_vbft_signatures(header, key_height)
+            },
+            VerifierType::StaticMultisig => self.verify_static_multisig_header(header)
+        }
+    }
+
+    fn verify_poly_vbft_header(&self, header: &Header) -> SCResult<()> {
+        let key_height = match self.find_key_height(header.chain_id, header.height) {
             Some(k) => k,
             None => return sc_error!("Couldn't find key height!")
         };
-        let prev_consensus = self.get_consensus_peers(chain_id, key_height);
 
-        if header.book_keepers.len() * 3 < prev_consensus.len() * 2 {
-            return sc_error!("Header bookkeepers num must be > 2/3 of consensus num");
+        // a consensus change is verified against the previous consensus, the one active at key_height
+        sc_try!(self.check_poly_vbft_consensus_payload(header));
+        if let Some(consensus_payload) = &header.consensus_payload {
+            sc_try!(self.check_block_vrf(header, consensus_payload, key_height));
         }
 
-        for bk in &header.book_keepers {
-            let key_id = hex_converter::byte_slice_to_hex(bk.as_slice());
-            
+        self.verify_poly_vbft_signatures(header, key_height)
+    }
+
+    /// the bookkeepers must be unique members of the consensus set at _key_height_
+    fn verify_poly_vbft_signatures(&self, header: &Header, key_height: u32) -> SCResult<()> {
+        let chain_id = header.chain_id;
+        let consensus_peer_count = self.get_consensus_peer_count(chain_id, key_height);
+
+        for (i, bk) in header.book_keepers.iter().enumerate() {
+            // the same key may be sent compressed or not
+            let compressed_key = bk.to_compressed_bytes();
+            if header.book_keepers[..i].iter().any(|k| k.to_compressed_bytes() == compressed_key) {
+                return sc_error!("Duplicate bookkeeper!");
+            }
+
             // if key doesn't exist, something is wrong
-            if !prev_consensus.iter().any(|p| p.id == key_id) {
+            if self.get_consensus_peer_index(chain_id, key_height, &compressed_key) == 0 {
                 return sc_error!("Invalid pubkey!");
             }
         }
 
-        let hashed_header = BoxedBytes::from(self.hash_header(header).as_bytes());
+        for (i, sig) in header.sig_data.iter().enumerate() {
+            if header.sig_data[..i].contains(sig) {
+                return sc_error!("Duplicate signature!");
+            }
+        }
+
+        let hashed_header = BoxedBytes::from(sc_try!(self.hash_header(header)).as_bytes());
+
+        let signers = sc_try!(self.verify_multi_signature(&hashed_header, &header.book_keepers, 
+            &header.sig_data));
+
+        // only unique consensus members with a valid signature count towards the quorum
+        if signers * 3 < consensus_peer_count * 2 {
+            return sc_error!("Header signers num must be > 2/3 of consensus num");
+        }
 
-        self.verify_multi_signature(&hashed_header, &header.book_keepers, 
-            header.book_keepers.len(), &header.sig_data)
+        Ok(())
     }
 
-    // private
+    /// The proposer must be in the pos table of the consensus active at _key_height_, and the VRF value and proof must be present.
+    /// The proof itself can't be checked: VBFT derives the VRF input with sha512 over a JSON encoding of the previous block's VRF value,
+    /// and neither sha512 nor VRF verification are available in the API.
+    /// Consensus changes rely on the VRF, so they're rejected unless the owner chose to accept unverified VRF proofs for the chain.
+    fn check_block_vrf(&self, header: &Header, consensus_payload: &VbftBlockInfo, key_height: u32) -> SCResult<()> {
+        let chain_id = header.chain_id;
+
+        require!(!consensus_payload.vrf_value.is_empty(), "Missing VRF value");
+        require!(!consensus_payload.vrf_proof.is_empty(), "Missing VRF proof");
+
+        let chain_config = self.get_chain_config(chain_id, key_height);
+        require!(
+            chain_config.pos_table.contains(&consensus_payload.proposer),
+            "Proposer is not in the pos table"
+        );
+        require!(
+            chain_config.peers.iter()
+                .any(|peer| peer.index == consensus_payload.proposer && self.peer_public_key(peer).is_some()),
+            "Proposer is not in the consensus"
+        );
+
+        if consensus_payload.new_chain_config.is_some() {
+            require!(
+                self.is_unverified_vrf_accepted(chain_id),
+                "VRF proofs can't be verified, so consensus changes are not accepted for this chain"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// bookkeepers are ignored, signatures are checked against the owner-set keys
+    fn verify_static_multisig_header(&self, header: &Header) -> SCResult<()> {
+        let chain_id = header.chain_id;
+        let keys = self.get_multisig_keys(chain_id);
+        require!(!keys.is_empty(), "Multisig keys not set");
+
+        for (i, sig) in header.sig_data.iter().enumerate() {
+            if header.sig_data[..i].contains(sig) {
+                return sc_error!("Duplicate signature!");
+            }
+        }
+
+        let hashed_header = BoxedBytes::from(sc_try!(self.hash_header(header)).as_bytes());
+        let signers = sc_try!(self.verify_multi_signature(&hashed_header, &keys, &header.sig_data));
+
+        if signers < self.get_multisig_threshold(chain_id) as usize {
+            return sc_error!("Not enough valid multisig signatures");
+        }
+
+        Ok(())
+    }
 
     fn update_consensus_peer(&self, header: &Header) -> SCResult<()> {
-        if let Some(consensus_payload) = &header.consensus_payload {
-            if let Some(chain_config) = &consensus_payload.new_chain_config {
+        match self.get_verifier_type_or_default(header.chain_id) {
+            VerifierType::PolyVbft => self.update_poly_vbft_consensus(header),
+            VerifierType::StaticMultisig => {
+                // the key set is managed by the owner
+                require!(
+                    header.consensus_payload.is_none(),
+                    "Consensus changes are not supported for this chain"
+                );
+
+                Ok(())
+            }
+        }
+    }
 
-                let chain_id = header.chain_id;
-                let height = header.height;
+    /// The genesis header is trusted, so its consensus payload is only checked here. Other headers were checked by verify_header.
+    fn update_poly_vbft_consensus(&self, header: &Header) -> SCResult<()> {
+        if self.is_empty_genesis_header(header.chain_id) {
+            sc_try!(self.check_poly_vbft_consensus_payload(header));
+        }
 
-                // update key heights
-                let mut key_heights = self.get_key_height_list(chain_id);
-                key_heights.push(height);
-                self.set_key_height_list(chain_id, &key_heights);
+        if let Some(chain_config) = header.consensus_payload.as_ref()
+            .and_then(|payload| payload.new_chain_config.as_ref()) {
+            let chain_id = header.chain_id;
+            let height = header.height;
 
-                // update consensus peer list
-                if !chain_config.peers.is_empty() {
-                    self.set_consensus_peers(chain_id, height, &chain_config.peers);
-                }
-                else {
-                    return sc_error!("Consensus peer list is empty!")
-                }
+            // update key heights
+            let mut key_heights = self.get_key_height_list(chain_id);
+            key_heights.push(height);
+            self.set_key_height_list(chain_id, &key_heights);
+
+            // update consensus peer list
+            self.set_chain_config(chain_id, height, chain_config);
+            self.store_consensus_peers(chain_id, height, &chain_config.peers);
+        }
+
+        Ok(())
+    }
+
+    /// The genesis header must set the initial consensus, and is the first key header.
+    /// A key header is its own last config block. Other headers must point to the latest key height.
+    fn check_poly_vbft_consensus_payload(&self, header: &Header) -> SCResult<()> {
+        let chain_id = header.chain_id;
+        let height = header.height;
+        let is_genesis = self.is_empty_genesis_header(chain_id);
+
+        let consensus_payload = match &header.consensus_payload {
+            Some(consensus_payload) => consensus_payload,
+            None => {
+                require!(!is_genesis, "Genesis header must set the initial consensus");
+
+                return Ok(());
             }
+        };
+
+        let chain_config = match &consensus_payload.new_chain_config {
+            Some(chain_config) => chain_config,
+            None => {
+                require!(!is_genesis, "Genesis header must set the initial consensus");
+                require!(
+                    self.find_key_height(chain_id, height) == Some(consensus_payload.last_config_block_num),
+                    "Last config block num does not match the stored key heights"
+                );
+
+                return Ok(());
+            }
+        };
+
+        require!(
+            consensus_payload.last_config_block_num == height,
+            "Last config block num of a consensus change must be its own height"
+        );
+        if !is_genesis {
+            require!(
+                header.is_start_of_epoch(self.get_epoch_length_or_default(chain_id)),
+                "Consensus changes are only accepted at the start of an epoch"
+            );
         }
 
+        sc_try!(self.validate_chain_config(chain_config));
+        require!(
+            self.next_book_keeper_matches(header, chain_config),
+            "Next bookkeeper does not match the new consensus"
+        );
+
         Ok(())
     }
 
+    /// Derives the bookkeeper address of the new consensus like Poly does, from the peers' public keys.
+    /// _chain_config_ must be validated first, so that all peer ids decode to keys.
+    fn next_book_keeper_matches(&self, header: &Header, chain_config: &ChainConfig) -> bool {
+        let keys: Vec<PublicKey> = chain_config.peers.iter()
+            .filter_map(|peer| self.peer_public_key(peer))
+            .collect();
+
+        let program = bookkeeper::bookkeeper_program(&keys);
+        let next_book_keeper = PolyAddress::from(ripemd160::ripemd160(self.sha256(&program).as_bytes()));
+
+        header.next_book_keeper == next_book_keeper
+    }
+
+    /// None if the peer's id is not a hex-encoded, well-formed public key
+    fn peer_public_key(&self, peer: &PeerConfig) -> Option<PublicKey> {
+        hex_converter::hex_to_byte_slice(peer.id.as_slice())
+            .and_then(|key| PublicKey::try_from_slice(key.as_slice()).ok())
+    }
+
+    fn clear_consensus(&self, chain_id: u64, key_height: u32) {
+        for peer in self.get_chain_config(chain_id, key_height).peers {
+            if let Some(key) = self.peer_public_key(&peer) {
+                self.clear_consensus_peer_index(chain_id, key_height, &key.to_compressed_bytes());
+            }
+        }
+
+        self.clear_consensus_peer_count(chain_id, key_height);
+        self.clear_chain_config(chain_id, key_height);
+    }
+
+    /// the peer ids were already checked by validate_chain_config, so they decode to valid keys
+    fn store_consensus_peers(&self, chain_id: u64, height: u32, peers: &[PeerConfig]) {
+        for peer in peers {
+            if let Some(key) = self.peer_public_key(peer) {
+                self.set_consensus_peer_index(chain_id, height, &key.to_compressed_bytes(), peer.index);
+            }
+        }
+
+        self.set_consensus_peer_count(chain_id, height, peers.len());
+    }
+
+    /// peer indexes are 1-based, and the pos table is made of peer indexes
+    fn validate_chain_config(&self, chain_config: &ChainConfig) -> SCResult<()> {
+        let peers = &chain_config.peers;
+        let network_size = chain_config.network_size as usize;
+
+        require!(!peers.is_empty(), "Consensus peer list is empty!");
+        require!(network_size == peers.len(), "Network size does not match the peer count");
+        require!(
+            chain_config.consensus_quorum > 0 && chain_config.consensus_quorum <= chain_config.network_size,
+            "Consensus quorum must be between 1 and the network size"
+        );
+
+        let mut index_used = Vec::with_capacity(network_size + 1);
+        index_used.resize(network_size + 1, false);
+
+        // ids are compared as compressed keys, as the same key may be hex-encoded in different cases, compressed or not
+        let mut peer_keys = Vec::with_capacity(network_size);
+
+        for peer in peers.iter() {
+            let index = peer.index as usize;
+
+            require!(index >= 1 && index <= network_size, "Peer index out of range");
+            require!(!index_used[index], "Duplicate peer index");
+            index_used[index] = true;
+
+            let compressed_key = match self.peer_public_key(peer) {
+                Some(key) => key.to_compressed_bytes(),
+                None => return sc_error!("Peer id is not a valid public key"),
+            };
+
+            require!(!peer_keys.contains(&compressed_key), "Duplicate peer id");
+            peer_keys.push(compressed_key);
+        }
+
+        for index in &chain_config.pos_table {
+            let index = *index as usize;
+
+            require!(
+                index >= 1 && index <= network_size,
+                "Pos table references a non-existent peer index"
+            );
+        }
+
+        Ok(())
+    }
+
+    fn require_header_sync_not_paused(&self, chain_id: u64) -> SCResult<()> {
+        require!(!self.is_header_sync_paused(), "Header sync is paused");
+        require!(!self.is_chain_paused(chain_id), "Header sync for this chain is paused");
+
+        Ok(())
+    }
+
+    // anyone may sync headers, but only approved relayers are rewarded
+    fn credit_header_sync_reward(&self, relayer: &Address) {
+        if self.is_empty_sync_block_header_reward_token()
+            || !self.get_approved_address_list().contains(relayer)
+        {
+            return;
+        }
+
+        self.relayer_rewards().credit_relayer_reward(
+            relayer,
+            &self.get_sync_block_header_reward_token(),
+            &self.get_sync_block_header_reward(),
+        );
+    }
+
+    // the stored header is kept as it is, and the conflicting one is saved as evidence
+    fn record_fork(&self, stored_header_hash: &H256, conflicting_header: &Header, conflicting_header_hash: &H256) {
+        let chain_id = conflicting_header.chain_id;
+        let height = conflicting_header.height;
+
+        if !self.is_empty_fork_evidence(chain_id, height) {
+            return;
+        }
+
+        self.set_fork_evidence(chain_id, height, conflicting_header);
+
+        let mut fork_heights = self.get_fork_heights(chain_id);
+        fork_heights.push(height);
+        self.set_fork_heights(chain_id, &fork_heights);
+
+        self.set_chain_frozen(chain_id, true);
+
+        self.fork_detected_event(
+            stored_header_hash,
+            conflicting_header_hash,
+            conflicting_header,
+        );
+    }
+
+    /// Poly's merkle proof: the leaf is hashed with a 0x00 prefix, then each step of the audit path,
+    /// a position byte followed by the sibling hash, is hashed with a 0x01 prefix.
+    /// Position 0 means the sibling is on the left.
+    fn compute_merkle_root(&self, merkle_value: &[u8], audit_path: &[u8]) -> SCResult<H256> {
+        let steps = audit_path.chunks_exact(1 + H256::len_bytes());
+        require!(steps.remainder().is_empty(), "Invalid audit path length");
+
+        let mut leaf = Vec::with_capacity(1 + merkle_value.len());
+        leaf.push(MERKLE_LEAF_PREFIX);
+        leaf.extend_from_slice(merkle_value);
+
+        let mut hash = self.sha256(&leaf);
+        for step in steps {
+            let sibling = &step[1..];
+
+            let mut node = Vec::with_capacity(1 + 2 * H256::len_bytes());
+            node.push(MERKLE_NODE_PREFIX);
+            if step[0] == 0 {
+                node.extend_from_slice(sibling);
+                node.extend_from_slice(hash.as_bytes());
+            } else {
+                node.extend_from_slice(hash.as_bytes());
+                node.extend_from_slice(sibling);
+            }
+
+            hash = self.sha256(&node);
+        }
+
+        Ok(hash)
+    }
+
     // header-related
 
     /// hashed twice, for some reason
-    fn hash_header(&self, header: &Header) -> H256 {
-        self.sha256(self.sha256(header.get_partial_serialized().as_slice()).as_bytes())
+    fn hash_header(&self, header: &Header) -> SCResult<H256> {
+        let serialized = match header.get_partial_serialized() {
+            core::result::Result::Ok(serialized) => serialized,
+            core::result::Result::Err(err) => return Err(SCError::from(err))
+        };
+
+        Ok(self.sha256(self.sha256(serialized.as_slice()).as_bytes()))
     }
 
-    fn store_header(&self, header: &Header) {
-        self.set_header_by_hash(header.chain_id, &header.block_hash, header);
-        self.set_header_by_height(header.chain_id, header.height, header);
+    /// headers are stored by the hash computed by the contract, not the one received in the header
+    fn store_header(&self, header: &Header, hash: &H256) {
+        self.set_header_record(header.chain_id, hash, &HeaderRecord::from_header(header, hash.clone()));
+        self.set_header_hash_by_height(header.chain_id, header.height, hash);
         self.set_current_height(header.chain_id, header.height);
+        self.set_latest_header_timestamp(header.chain_id, header.timestamp as u64);
+    }
+
+    // key headers and headers at the start of an epoch are never pruned, so they're not added to the queue
+    fn add_header_to_prune_queue(&self, header: &Header) {
+        let is_key_header = match &header.consensus_payload {
+            Some(payload) => payload.new_chain_config.is_some(),
+            None => false
+        };
+
+        if is_key_header || header.is_start_of_epoch(self.get_epoch_length_or_default(header.chain_id)) {
+            return;
+        }
+
+        let queue_len = self.get_prune_queue_length(header.chain_id);
+
+        self.set_prune_queue_height(header.chain_id, queue_len, header.height);
+        self.set_prune_queue_length(header.chain_id, queue_len + 1);
+    }
+
+    /// Headers are pruned in the order they were synced, and at most MAX_PRUNED_HEADERS_PER_SYNC at a time.
+    /// Stops at the first header still within the retention window.
+    fn prune_headers(&self, chain_id: u64, latest_height: u32) {
+        let retention_blocks = self.get_header_retention(chain_id);
+        if retention_blocks == 0 {
+            return;
+        }
+
+        let queue_len = self.get_prune_queue_length(chain_id);
+        let mut current_index = self.get_prune_queue_current_index(chain_id);
+        let mut pruned = 0;
+
+        while pruned < MAX_PRUNED_HEADERS_PER_SYNC && current_index < queue_len {
+            let height = self.get_prune_queue_height(chain_id, current_index);
+            if height as u64 + retention_blocks as u64 >= latest_height as u64 {
+                break;
+            }
+
+            if !self.is_empty_header_hash_by_height(chain_id, height) {
+                let hash = self.get_header_hash_by_height(chain_id, height);
+
+                self.clear_header_record(chain_id, &hash);
+                self.clear_header_hash_by_height(chain_id, height);
+            }
+
+            self.clear_prune_queue_height(chain_id, current_index);
+            current_index += 1;
+            pruned += 1;
+        }
+
+        self.set_prune_queue_current_index(chain_id, current_index);
+    }
+
+    fn check_header_timestamp(&self, header: &Header) -> SCResult<()> {
+        let timestamp = header.timestamp as u64;
+
+        require!(
+            timestamp >= self.get_latest_header_timestamp(header.chain_id),
+            "Header timestamp is lower than the latest synced header's timestamp"
+        );
+
+        if !self.is_empty_max_header_future_tolerance() {
+            require!(
+                timestamp <= self.get_block_timestamp() + self.get_max_header_future_tolerance(),
+                "Header timestamp is too far in the future"
+            );
+        }
+
+        Ok(())
     }
 
     // verification-related
@@ -169,17 +1042,28 @@ pub trait BlockHeaderSync {
         }
     }
 
-    // TO DO: verify function not yet available in API
-    fn verify(&self, _data: &BoxedBytes, _key: &PublicKey, _sig: &Signature) -> bool {
-        true
+    /// Unlike find_key_height, searches the whole key height list, so it also works for old heights
+    fn find_key_height_at(&self, chain_id: u64, height: u32) -> Option<u32> {
+        self.get_key_height_list(chain_id)
+            .iter()
+            .rev()
+            .find(|k| **k <= height)
+            .copied()
     }
 
-    fn verify_multi_signature(&self, data: &BoxedBytes, keys: &[PublicKey], 
-        min_sigs: usize, sigs: &[Signature]) -> SCResult<()> {
-
-        if sigs.len() < min_sigs {
-            return sc_error!("Not enough signatures!");
+    /// dispatches on the key type, keys the API can't verify are rejected
+    fn verify(&self, data: &BoxedBytes, key: &PublicKey, sig: &Signature) -> bool {
+        match (key.algorithm(), key.curve()) {
+            (KeyAlgorithm::EdDsa, Curve::Ed25519) => self.verify_ed25519(key.point(), data.as_slice(), sig.raw()),
+            (KeyAlgorithm::Ecdsa, Curve::Secp256k1) => self.verify_secp256k1(key.point(), data.as_slice(), sig.to_der().as_slice()),
+            // TO DO: other curves not yet available in API
+            _ => false
         }
+    }
+
+    /// returns the number of keys with a valid signature. Each key can only be matched by one signature.
+    fn verify_multi_signature(&self, data: &BoxedBytes, keys: &[PublicKey], 
+        sigs: &[Signature]) -> SCResult<usize> {
 
         let mut mask = Vec::with_capacity(keys.len());
         mask.resize(keys.len(), false);
@@ -204,7 +1088,7 @@ pub trait BlockHeaderSync {
             }
         }
 
-        Ok(())
+        Ok(mask.iter().filter(|signed| **signed).count())
     }
 
     // events
@@ -212,62 +1096,308 @@ pub trait BlockHeaderSync {
     #[event("0x1000000000000000000000000000000000000000000000000000000000000001")]
     fn block_header_sync_event(&self, header: &Header);
 
+    #[event("0x1000000000000000000000000000000000000000000000000000000000000002")]
+    fn fork_detected_event(&self, stored_header_hash: &H256, conflicting_header_hash: &H256, conflicting_header: &Header);
+
     // storage
 
     // genesis header
 
     #[storage_get("genesisHeader")]
-    fn get_genesis_header(&self) -> Header;
+    fn get_genesis_header(&self, chain_id: u64) -> Header;
 
     #[storage_set("genesisHeader")]
-    fn set_genesis_header(&self, header: &Header);
+    fn set_genesis_header(&self, chain_id: u64, header: &Header);
 
     #[storage_is_empty("genesisHeader")]
-    fn is_empty_genesis_header(&self) -> bool;
+    fn is_empty_genesis_header(&self, chain_id: u64) -> bool;
+
+    // verifier type
+
+    #[storage_get("verifierType")]
+    fn get_verifier_type(&self, chain_id: u64) -> VerifierType;
 
-    // header by hash
+    #[storage_set("verifierType")]
+    fn set_verifier_type(&self, chain_id: u64, verifier_type: VerifierType);
 
-    #[storage_get("headerByHash")]
-    fn get_header_by_hash(&self, chain_id: u64, hash: &H256) -> Header;
+    #[storage_is_empty("verifierType")]
+    fn is_empty_verifier_type(&self, chain_id: u64) -> bool;
 
-    #[storage_set("headerByHash")]
-    fn set_header_by_hash(&self, chain_id: u64, hash: &H256, header: &Header);
+    // static multisig
 
-    #[storage_is_empty("headerByHash")]
-    fn is_empty_header_by_hash(&self, chain_id: u64, hash: &H256) -> bool;
+    #[view(getMultisigKeys)]
+    #[storage_get("multisigKeys")]
+    fn get_multisig_keys(&self, chain_id: u64) -> Vec<PublicKey>;
 
-    // header by height
+    #[storage_set("multisigKeys")]
+    fn set_multisig_keys(&self, chain_id: u64, keys: &[PublicKey]);
 
-    #[storage_get("headerByHeight")]
-    fn get_header_by_height(&self, chain_id: u64, height: u32) -> Header;
+    #[view(getMultisigThreshold)]
+    #[storage_get("multisigThreshold")]
+    fn get_multisig_threshold(&self, chain_id: u64) -> u32;
 
-    #[storage_set("headerByHeight")]
-    fn set_header_by_height(&self, chain_id: u64, height: u32, header: &Header);
+    #[storage_set("multisigThreshold")]
+    fn set_multisig_threshold(&self, chain_id: u64, threshold: u32);
 
-    #[storage_is_empty("headerByHeight")]
-    fn is_empty_header_by_height(&self, chain_id: u64, height: u32) -> bool;
+    // tracked chains - chains with a synced genesis header
+
+    #[view(getTrackedChains)]
+    #[storage_get("trackedChains")]
+    fn get_tracked_chains(&self) -> Vec<u64>;
+
+    #[storage_set("trackedChains")]
+    fn set_tracked_chains(&self, chain_ids: &[u64]);
+
+    // header records, by hash
+
+    #[storage_get("headerRecord")]
+    fn get_header_record(&self, chain_id: u64, hash: &H256) -> HeaderRecord;
+
+    #[storage_set("headerRecord")]
+    fn set_header_record(&self, chain_id: u64, hash: &H256, record: &HeaderRecord);
+
+    #[storage_clear("headerRecord")]
+    fn clear_header_record(&self, chain_id: u64, hash: &H256);
+
+    #[storage_is_empty("headerRecord")]
+    fn is_empty_header_record(&self, chain_id: u64, hash: &H256) -> bool;
+
+    // header hash by height
+
+    #[storage_get("headerHashByHeight")]
+    fn get_header_hash_by_height(&self, chain_id: u64, height: u32) -> H256;
+
+    #[storage_set("headerHashByHeight")]
+    fn set_header_hash_by_height(&self, chain_id: u64, height: u32, hash: &H256);
+
+    #[storage_clear("headerHashByHeight")]
+    fn clear_header_hash_by_height(&self, chain_id: u64, height: u32);
+
+    #[storage_is_empty("headerHashByHeight")]
+    fn is_empty_header_hash_by_height(&self, chain_id: u64, height: u32) -> bool;
+
+    // header retention - number of blocks after which headers are pruned, 0 means all headers are kept
+
+    #[view(getHeaderRetention)]
+    #[storage_get("headerRetention")]
+    fn get_header_retention(&self, chain_id: u64) -> u32;
+
+    #[storage_set("headerRetention")]
+    fn set_header_retention(&self, chain_id: u64, retention_blocks: u32);
+
+    // prune queue - heights of the headers that may be pruned, in the order they were synced
+
+    #[storage_get("pruneQueueHeight")]
+    fn get_prune_queue_height(&self, chain_id: u64, index: usize) -> u32;
+
+    #[storage_set("pruneQueueHeight")]
+    fn set_prune_queue_height(&self, chain_id: u64, index: usize, height: u32);
+
+    #[storage_clear("pruneQueueHeight")]
+    fn clear_prune_queue_height(&self, chain_id: u64, index: usize);
+
+    #[storage_get("pruneQueueLength")]
+    fn get_prune_queue_length(&self, chain_id: u64) -> usize;
+
+    #[storage_set("pruneQueueLength")]
+    fn set_prune_queue_length(&self, chain_id: u64, length: usize);
+
+    #[storage_get("pruneQueueCurrentIndex")]
+    fn get_prune_queue_current_index(&self, chain_id: u64) -> usize;
+
+    #[storage_set("pruneQueueCurrentIndex")]
+    fn set_prune_queue_current_index(&self, chain_id: u64, current_index: usize);
 
     // current height
 
+    #[view(getCurrentHeight)]
     #[storage_get("currentHeight")]
     fn get_current_height(&self, chain_id: u64) -> u32;
 
     #[storage_set("currentHeight")]
     fn set_current_height(&self, chain_id: u64, height: u32);
 
-    // consensus peers
+    // consensus - the chain config is stored as received, and the peers are also stored as a public key -> index lookup
+
+    #[storage_get("chainConfig")]
+    fn get_chain_config(&self, chain_id: u64, height: u32) -> ChainConfig;
+
+    #[storage_set("chainConfig")]
+    fn set_chain_config(&self, chain_id: u64, height: u32, chain_config: &ChainConfig);
 
-    #[storage_get("consensusPeers")]
-    fn get_consensus_peers(&self, chain_id: u64, height: u32) -> Vec<PeerConfig>;
+    #[storage_is_empty("chainConfig")]
+    fn is_empty_chain_config(&self, chain_id: u64, height: u32) -> bool;
 
-    #[storage_set("consensusPeers")]
-    fn set_consensus_peers(&self, chain_id: u64, height: u32, peers: &[PeerConfig]);
+    #[storage_clear("chainConfig")]
+    fn clear_chain_config(&self, chain_id: u64, height: u32);
+
+    // peer indexes are 1-based, so 0 means the key is not part of the consensus
+    // peers are stored under their compressed key, see PublicKey::to_compressed_bytes
+    #[storage_get("consensusPeerIndex")]
+    fn get_consensus_peer_index(&self, chain_id: u64, height: u32, compressed_key: &BoxedBytes) -> u32;
+
+    #[storage_set("consensusPeerIndex")]
+    fn set_consensus_peer_index(&self, chain_id: u64, height: u32, compressed_key: &BoxedBytes, index: u32);
+
+    #[storage_clear("consensusPeerIndex")]
+    fn clear_consensus_peer_index(&self, chain_id: u64, height: u32, compressed_key: &BoxedBytes);
+
+    #[view(getConsensusPeerCount)]
+    #[storage_get("consensusPeerCount")]
+    fn get_consensus_peer_count(&self, chain_id: u64, height: u32) -> usize;
+
+    #[storage_set("consensusPeerCount")]
+    fn set_consensus_peer_count(&self, chain_id: u64, height: u32, count: usize);
+
+    #[storage_clear("consensusPeerCount")]
+    fn clear_consensus_peer_count(&self, chain_id: u64, height: u32);
+
+    // epoch length - POLYCHAIN_EPOCH_HEIGHT is used if not set
+
+    #[storage_get("epochLength")]
+    fn get_epoch_length(&self, chain_id: u64) -> u32;
+
+    #[storage_set("epochLength")]
+    fn set_epoch_length(&self, chain_id: u64, epoch_length: u32);
+
+    #[storage_is_empty("epochLength")]
+    fn is_empty_epoch_length(&self, chain_id: u64) -> bool;
+
+    // consensus changes need a VRF proof that can't be verified, so they're only accepted if the owner chose to
+
+    #[view(isUnverifiedVrfAccepted)]
+    #[storage_get("acceptUnverifiedVrf")]
+    fn is_unverified_vrf_accepted(&self, chain_id: u64) -> bool;
+
+    #[storage_set("acceptUnverifiedVrf")]
+    fn set_accept_unverified_vrf(&self, chain_id: u64, accept: bool);
 
     // key height list
 
+    #[view(getKeyHeights)]
     #[storage_get("keyHeightList")]
     fn get_key_height_list(&self, chain_id: u64) -> Vec<u32>;
 
     #[storage_set("keyHeightList")]
     fn set_key_height_list(&self, chain_id: u64, list: &[u32]);
+
+    // relayer rewards - paid from the pool of the reward token, funded with the bridge fees forwarded by the reward funds source
+
+    #[module(RelayerRewardsModuleImpl)]
+    fn relayer_rewards(&self) -> RelayerRewardsModuleImpl<T, BigInt, BigUint>;
+
+    #[view(getSyncBlockHeaderRewardToken)]
+    #[storage_get("syncBlockHeaderRewardToken")]
+    fn get_sync_block_header_reward_token(&self) -> TokenIdentifier;
+
+    #[storage_set("syncBlockHeaderRewardToken")]
+    fn set_sync_block_header_reward_token(&self, token_identifier: &TokenIdentifier);
+
+    #[storage_is_empty("syncBlockHeaderRewardToken")]
+    fn is_empty_sync_block_header_reward_token(&self) -> bool;
+
+    #[view(getSyncBlockHeaderReward)]
+    #[storage_get("syncBlockHeaderReward")]
+    fn get_sync_block_header_reward(&self) -> BigUint;
+
+    #[storage_set("syncBlockHeaderReward")]
+    fn set_sync_block_header_reward(&self, reward: &BigUint);
+
+    // approved relayers, the only ones rewarded for syncing headers, mirrored from the relayer registry
+
+    #[view(getRelayerRegistryAddress)]
+    #[storage_get("relayerRegistryAddress")]
+    fn get_relayer_registry_address(&self) -> Address;
+
+    #[storage_set("relayerRegistryAddress")]
+    fn set_relayer_registry_address(&self, address: &Address);
+
+    #[storage_is_empty("relayerRegistryAddress")]
+    fn is_empty_relayer_registry_address(&self) -> bool;
+
+    #[view(getApprovedAddressList)]
+    #[storage_get("approvedAddressList")]
+    fn get_approved_address_list(&self) -> Vec<Address>;
+
+    #[storage_set("approvedAddressList")]
+    fn set_approved_address_list(&self, approved_address_list: &[Address]);
+
+    // pause - the guardian may pause, but only the owner may unpause
+
+    #[module(PauseModuleImpl)]
+    fn pause(&self) -> PauseModuleImpl<T, BigInt, BigUint>;
+
+    #[view(isHeaderSyncPaused)]
+    #[storage_get("headerSyncPaused")]
+    fn is_header_sync_paused(&self) -> bool;
+
+    #[storage_set("headerSyncPaused")]
+    fn set_header_sync_paused(&self, paused: bool);
+
+    #[view(isChainPaused)]
+    #[storage_get("chainPaused")]
+    fn is_chain_paused(&self, chain_id: u64) -> bool;
+
+    #[storage_set("chainPaused")]
+    fn set_chain_paused(&self, chain_id: u64, paused: bool);
+
+    // header timestamps
+
+    #[view(getLatestHeaderTimestamp)]
+    #[storage_get("latestHeaderTimestamp")]
+    fn get_latest_header_timestamp(&self, chain_id: u64) -> u64;
+
+    #[storage_set("latestHeaderTimestamp")]
+    fn set_latest_header_timestamp(&self, chain_id: u64, timestamp: u64);
+
+    #[view(getMaxHeaderFutureTolerance)]
+    #[storage_get("maxHeaderFutureTolerance")]
+    fn get_max_header_future_tolerance(&self) -> u64;
+
+    #[storage_set("maxHeaderFutureTolerance")]
+    fn set_max_header_future_tolerance(&self, tolerance: u64);
+
+    #[storage_is_empty("maxHeaderFutureTolerance")]
+    fn is_empty_max_header_future_tolerance(&self) -> bool;
+
+    #[view(getMaxHeaderAge)]
+    #[storage_get("maxHeaderAge")]
+    fn get_max_header_age(&self, chain_id: u64) -> u64;
+
+    #[storage_set("maxHeaderAge")]
+    fn set_max_header_age(&self, chain_id: u64, max_age: u64);
+
+    #[storage_is_empty("maxHeaderAge")]
+    fn is_empty_max_header_age(&self, chain_id: u64) -> bool;
+
+    // fork detection - a frozen chain can't sync headers or process inbound transactions until the fork is resolved
+
+    #[view(isChainFrozen)]
+    #[storage_get("chainFrozen")]
+    fn is_chain_frozen(&self, chain_id: u64) -> bool;
+
+    #[storage_set("chainFrozen")]
+    fn set_chain_frozen(&self, chain_id: u64, frozen: bool);
+
+    #[storage_get("forkEvidence")]
+    fn get_fork_evidence(&self, chain_id: u64, height: u32) -> Header;
+
+    #[storage_set("forkEvidence")]
+    fn set_fork_evidence(&self, chain_id: u64, height: u32, header: &Header);
+
+    #[storage_clear("forkEvidence")]
+    fn clear_fork_evidence(&self, chain_id: u64, height: u32);
+
+    #[storage_is_empty("forkEvidence")]
+    fn is_empty_fork_evidence(&self, chain_id: u64, height: u32) -> bool;
+
+    #[view(getForkHeights)]
+    #[storage_get("forkHeights")]
+    fn get_fork_heights(&self, chain_id: u64) -> Vec<u32>;
+
+    #[storage_set("forkHeights")]
+    fn set_fork_heights(&self, chain_id: u64, heights: &[u32]);
+
+    #[storage_clear("forkHeights")]
+    fn clear_fork_heights(&self, chain_id: u64);
 }
@@ -14,6 +14,8 @@ FROM_ERD_TX_HASH=0xd95c06a936c765969c42846432d41268fd73c7a169e10ad1543050a4431ed
 # Tx from Ethereum (just an example, could be any chain) to Elrond
 FROM_ETH_TRANSACTION=0x25a4fa887af0bb300e21a4bf8c6a7101a17c2039af36ae9b33b32ee962e64039000000000000000000000000000000000000000000000000000000000000000000000000000000002a000000000000000139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e10000
 FROM_ETH_TX_HASH=0x25a4fa887af0bb300e21a4bf8c6a7101a17c2039af36ae9b33b32ee962e64039
+# Proves the tx against the cross state root of its header, empty if it's the only tx in the merkle tree
+FROM_ETH_AUDIT_PATH=0x
 
 loadNonce() {
     alice_nonce=$(erdpy data load --key=alice_nonce)
@@ -236,7 +238,7 @@ receiveSixWrappedEth() {
     source ../CrossChainManagement/interaction/snippets.sh
 
     loadNonce
-    processCrossChainTx 0x00 0x00 ${FROM_ETH_TRANSACTION} ${WRAPPED_ETH_TOKEN_IDENTIFIER} 0x06
+    processCrossChainTx 0x00 0x00 ${FROM_ETH_TRANSACTION} ${FROM_ETH_AUDIT_PATH} ${WRAPPED_ETH_TOKEN_IDENTIFIER} 0x06
     storeIncrementNonce
 }
 
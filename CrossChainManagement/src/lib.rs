@@ -1,11 +1,14 @@
 #![no_std]
+#![allow(clippy::too_many_arguments)]
 
-use elrond_wasm::{imports, only_owner, HexCallDataSerializer};
+use elrond_wasm::{derive_imports, imports, only_owner, HexCallDataSerializer};
 use esdt_payment::*;
-use header::*;
+use pause::*;
+use relayer_rewards::*;
 use transaction::*;
 
 imports!();
+derive_imports!();
 
 const TRANSFER_ESDT_ENDPOINT_NAME: &[u8] = b"transferEsdt";
 
@@ -18,17 +21,44 @@ const ESDT_SYSTEM_SC_ADDRESS_ARRAY: [u8; 32] = [
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xff,
 ];
 
-#[elrond_wasm_derive::callable(BlockHeaderSyncProxy)]
-pub trait BlockHeaderSync {
-    #[rustfmt::skip]
-	#[callback(get_header_by_height_callback)]
-    fn getHeaderByHeight(&self, chain_id: u64, height: u32,
-        #[callback_arg] tx: &Transaction,
-        #[callback_arg] token_identifier: &TokenIdentifier,
-        #[callback_arg] amount: &BigUint
-    );
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum TransferDirection {
+    Outbound,
+    Inbound,
+}
+
+/// Limits for one (token, chain, direction) route.
+/// Volume is limited over a rolling window of _period_seconds_ (see `TransferVolume`).
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct TransferLimits<BigUint: BigUintApi> {
+    pub min_amount_per_tx: BigUint,
+    pub max_amount_per_tx: BigUint,
+    pub max_volume_per_period: BigUint,
+    pub period_seconds: u64,
+}
+
+/// The rolling window is approximated from consecutive periods: the volume of the previous period
+/// counts in proportion to how much of it is still inside the window ending at the current timestamp.
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct TransferVolume<BigUint: BigUintApi> {
+    pub period_start: u64,
+    pub volume: BigUint,
+    pub previous_volume: BigUint,
 }
 
+const VERIFY_INBOUND_TX_ENDPOINT_NAME: &[u8] = b"verifyInboundTx";
+const CHECK_CHAIN_TRUSTED_ENDPOINT_NAME: &[u8] = b"checkChainTrusted";
+const DEPOSIT_REWARD_FUNDS_ENDPOINT_NAME: &[u8] = b"depositRewardFunds";
+const SET_APPROVED_RELAYER_ENDPOINT_NAME: &[u8] = b"setApprovedRelayer";
+
+// the destination chain reports the outcome of each of our transactions through a Poly transaction
+// calling txStatus, with the transaction's hash and its status as arguments
+const TX_STATUS_RECEIPT_METHOD_NAME: &[u8] = b"txStatus";
+
+// the first arguments of an inbound transaction are the token identifier and the amount (big endian) it transfers,
+// both empty if it doesn't transfer anything, followed by the arguments of the method it calls
+const INBOUND_TX_PAYMENT_ARGS_LEN: usize = 2;
+
 #[elrond_wasm_derive::contract(CrossChainManagementImpl)]
 pub trait CrossChainManagement {
     #[init]
@@ -86,13 +116,7 @@ pub trait CrossChainManagement {
     fn add_address_to_approved_list(&self, approved_address: Address) -> SCResult<()> {
         only_owner!(self, "only owner may call this function");
 
-        let mut approved_address_list = self.get_approved_address_list();
-
-        if !approved_address_list.contains(&approved_address) {
-            approved_address_list.push(approved_address);
-
-            self.set_approved_address_list(&approved_address_list);
-        }
+        self.add_address_to_approved_list_if_missing(&approved_address);
 
         Ok(())
     }
@@ -101,17 +125,7 @@ pub trait CrossChainManagement {
     fn remove_address_from_approved_list(&self, approved_address: Address) -> SCResult<()> {
         only_owner!(self, "only owner may call this function");
 
-        let mut approved_address_list = self.get_approved_address_list();
-
-        for i in 0..approved_address_list.len() {
-            if approved_address_list[i] == approved_address {
-                approved_address_list.remove(i);
-
-                self.set_approved_address_list(&approved_address_list);
-
-                break;
-            }
-        }
+        self.remove_address_from_approved_list_if_present(&approved_address);
 
         Ok(())
     }
@@ -192,6 +206,423 @@ pub trait CrossChainManagement {
         Ok(())
     }
 
+    #[endpoint(setRelayerMinBond)]
+    fn set_relayer_min_bond_endpoint(&self, min_bond: BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(min_bond > 0, "Min bond must be more than 0");
+
+        self.set_relayer_min_bond(&min_bond);
+
+        Ok(())
+    }
+
+    #[endpoint(setRelayerUnbondPeriod)]
+    fn set_relayer_unbond_period_endpoint(&self, unbond_period: u64) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_relayer_unbond_period(unbond_period);
+
+        Ok(())
+    }
+
+    #[endpoint(setInsurancePoolAddress)]
+    fn set_insurance_pool_address_endpoint(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_insurance_pool_address(&address);
+
+        Ok(())
+    }
+
+    /// Status receipts from _chain_id_ are only trusted if sent by _contract_address_, the bridge contract on that chain
+    #[endpoint(setTrustedBridgeContract)]
+    fn set_trusted_bridge_contract_endpoint(
+        &self,
+        chain_id: u64,
+        contract_address: Address,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_trusted_bridge_contract(chain_id, &contract_address);
+
+        Ok(())
+    }
+
+    /// _quorum_percentage_ of the other bonded relayers must vote for a slash
+    #[endpoint(setSlashQuorum)]
+    fn set_slash_quorum_endpoint(&self, quorum_percentage: usize) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(
+            quorum_percentage > 0 && quorum_percentage <= 100,
+            "Quorum must be between 1 and 100 percent"
+        );
+
+        self.set_slash_quorum(quorum_percentage);
+
+        Ok(())
+    }
+
+    /// Charged on outbound transfers of _token_identifier_, and added to the reward pool of that token
+    #[endpoint(setBridgeFee)]
+    fn set_bridge_fee_endpoint(
+        &self,
+        token_identifier: TokenIdentifier,
+        fee: BigUint,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_bridge_fee(&token_identifier, &fee);
+
+        Ok(())
+    }
+
+    /// Moves collected bridge fees to the header sync contract, to reward the relayers syncing headers
+    #[endpoint(forwardRewardFunds)]
+    fn forward_reward_funds(
+        &self,
+        token_identifier: TokenIdentifier,
+        amount: BigUint,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(amount > 0, "Amount must be more than 0");
+
+        let mut reward_pool_balance = self.relayer_rewards().get_reward_pool_balance(&token_identifier);
+        require!(amount <= reward_pool_balance, "Not enough reward funds");
+
+        reward_pool_balance -= &amount;
+        self.relayer_rewards()
+            .set_reward_pool_balance(&token_identifier, &reward_pool_balance);
+
+        let mut serializer = HexCallDataSerializer::new(ESDT_TRANSFER_STRING);
+        serializer.push_argument_bytes(token_identifier.as_slice());
+        serializer.push_argument_bytes(&amount.to_bytes_be());
+        serializer.push_argument_bytes(DEPOSIT_REWARD_FUNDS_ENDPOINT_NAME);
+
+        // TODO: Replace with send_esdt
+        self.send().direct_egld(
+            &self.get_header_sync_contract_address(),
+            &BigUint::zero(),
+            serializer.as_slice(),
+        );
+
+        Ok(())
+    }
+
+    #[endpoint(setProcessCrossChainTxReward)]
+    fn set_process_cross_chain_tx_reward_endpoint(
+        &self,
+        token_identifier: TokenIdentifier,
+        reward: BigUint,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_process_cross_chain_tx_reward(&token_identifier, &reward);
+
+        Ok(())
+    }
+
+    #[endpoint(setProcessPendingTxReward)]
+    fn set_process_pending_tx_reward_endpoint(
+        &self,
+        token_identifier: TokenIdentifier,
+        reward: BigUint,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_process_pending_tx_reward(&token_identifier, &reward);
+
+        Ok(())
+    }
+
+    #[endpoint(setTransferLimits)]
+    fn set_transfer_limits_endpoint(
+        &self,
+        token_identifier: TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+        min_amount_per_tx: BigUint,
+        max_amount_per_tx: BigUint,
+        max_volume_per_period: BigUint,
+        period_seconds: u64,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(
+            min_amount_per_tx <= max_amount_per_tx,
+            "Min amount per tx must not be more than max amount per tx"
+        );
+        require!(
+            max_amount_per_tx <= max_volume_per_period,
+            "Max amount per tx must not be more than max volume per period"
+        );
+        require!(period_seconds > 0, "Period must be more than 0");
+
+        self.set_transfer_limits(
+            &token_identifier,
+            chain_id,
+            direction,
+            &TransferLimits {
+                min_amount_per_tx,
+                max_amount_per_tx,
+                max_volume_per_period,
+                period_seconds,
+            },
+        );
+
+        Ok(())
+    }
+
+    #[endpoint(removeTransferLimits)]
+    fn remove_transfer_limits(
+        &self,
+        token_identifier: TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.clear_transfer_limits(&token_identifier, chain_id, direction);
+        self.clear_transfer_volume(&token_identifier, chain_id, direction);
+
+        Ok(())
+    }
+
+    /// Queued transactions bypass the transfer limits once released
+    #[endpoint(releaseQueuedTx)]
+    fn release_queued_tx(&self, poly_tx_hash: H256) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+        require!(
+            self.get_tx_status(&poly_tx_hash) == TransactionStatus::Queued,
+            "Transaction is not in Queued status"
+        );
+
+        self.check_source_chain_trusted(&poly_tx_hash);
+
+        let mut queued_tx_list = self.get_queued_tx_list();
+        if let Some(index) = queued_tx_list.iter().position(|hash| hash == &poly_tx_hash) {
+            queued_tx_list.remove(index);
+
+            self.set_queued_tx_list(&queued_tx_list);
+        }
+
+        self.set_tx_status(&poly_tx_hash, TransactionStatus::Pending);
+
+        Ok(())
+    }
+
+    #[endpoint(unpauseOutbound)]
+    fn unpause_outbound(&self) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_outbound_paused(false);
+
+        Ok(())
+    }
+
+    #[endpoint(unpauseInbound)]
+    fn unpause_inbound(&self) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_inbound_paused(false);
+
+        Ok(())
+    }
+
+    #[endpoint(unpauseToken)]
+    fn unpause_token(&self, token_identifier: TokenIdentifier) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_token_paused(&token_identifier, false);
+
+        Ok(())
+    }
+
+    #[endpoint(unpauseChain)]
+    fn unpause_chain(&self, chain_id: u64) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_chain_paused(chain_id, false);
+
+        Ok(())
+    }
+
+    // endpoints - guardian or owner
+
+    #[endpoint(pauseOutbound)]
+    fn pause_outbound(&self) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_outbound_paused(true);
+
+        Ok(())
+    }
+
+    #[endpoint(pauseInbound)]
+    fn pause_inbound(&self) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_inbound_paused(true);
+
+        Ok(())
+    }
+
+    #[endpoint(pauseToken)]
+    fn pause_token(&self, token_identifier: TokenIdentifier) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_token_paused(&token_identifier, true);
+
+        Ok(())
+    }
+
+    #[endpoint(pauseChain)]
+    fn pause_chain(&self, chain_id: u64) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_chain_paused(chain_id, true);
+
+        Ok(())
+    }
+
+    // endpoints - relayers
+
+    #[payable("EGLD")]
+    #[endpoint(bondRelayer)]
+    fn bond_relayer(&self, #[payment] payment: BigUint) -> SCResult<()> {
+        require!(
+            !self.is_empty_relayer_min_bond(),
+            "relayer min bond not set"
+        );
+        require!(payment > 0, "Payment must be more than 0");
+
+        let relayer = self.get_caller();
+        let mut bond = self.get_relayer_bond(&relayer);
+        bond += payment;
+
+        require!(
+            bond >= self.get_relayer_min_bond(),
+            "Bond is lower than the relayer min bond"
+        );
+
+        if !self.is_active_relayer(&relayer) {
+            self.set_active_relayer_count(self.get_active_relayer_count() + 1);
+        }
+
+        // bonding again cancels a pending unbond
+        self.clear_relayer_unbond_start_block(&relayer);
+        self.set_relayer_bond(&relayer, &bond);
+
+        self.add_address_to_approved_list_if_missing(&relayer);
+
+        Ok(())
+    }
+
+    #[endpoint(unbondRelayer)]
+    fn unbond_relayer(&self) -> SCResult<()> {
+        let relayer = self.get_caller();
+
+        require!(
+            self.get_relayer_bond(&relayer) > 0,
+            "Caller is not a bonded relayer"
+        );
+        require!(
+            self.is_empty_relayer_unbond_start_block(&relayer),
+            "Relayer is already unbonding"
+        );
+
+        self.remove_address_from_approved_list_if_present(&relayer);
+        self.set_relayer_unbond_start_block(&relayer, self.get_block_nonce());
+        self.set_active_relayer_count(self.get_active_relayer_count() - 1);
+
+        Ok(())
+    }
+
+    #[endpoint(withdrawRelayerBond)]
+    fn withdraw_relayer_bond(&self) -> SCResult<()> {
+        let relayer = self.get_caller();
+
+        require!(
+            !self.is_empty_relayer_unbond_start_block(&relayer),
+            "Relayer must unbond first"
+        );
+
+        let unbond_start_block = self.get_relayer_unbond_start_block(&relayer);
+        require!(
+            self.get_block_nonce() >= unbond_start_block + self.get_relayer_unbond_period(),
+            "Unbond period has not passed yet"
+        );
+
+        let bond = self.get_relayer_bond(&relayer);
+
+        self.clear_relayer_bond(&relayer);
+        self.clear_relayer_unbond_start_block(&relayer);
+
+        self.send().direct_egld(&relayer, &bond, b"relayer bond withdrawal");
+
+        Ok(())
+    }
+
+    /// The owner slashes directly. Bonded relayers vote, and the slash is performed once slashQuorum percent
+    /// of the other bonded relayers voted for it.
+    /// Either way, the slashed relayer's status vote on _poly_tx_hash_ must be contradicted by _receipt_,
+    /// the destination chain's txStatus receipt, proven by _audit_path_ against the header at _height_.
+    #[endpoint(slashRelayer)]
+    fn slash_relayer(
+        &self,
+        relayer: Address,
+        poly_tx_hash: H256,
+        from_chain_id: u64,
+        height: u32,
+        receipt: Transaction,
+        audit_path: BoxedBytes,
+    ) -> SCResult<()> {
+        require!(
+            !self.is_empty_insurance_pool_address(),
+            "insurance pool address not set"
+        );
+        require!(
+            self.get_relayer_bond(&relayer) > 0,
+            "Address is not a bonded relayer"
+        );
+        require!(
+            !self.is_empty_tx_status_voter(&poly_tx_hash)
+                && self.get_tx_status_voter(&poly_tx_hash) == relayer,
+            "Relayer did not vote on this transaction's status"
+        );
+        sc_try!(self.check_receipt_contradicts_vote(&poly_tx_hash, from_chain_id, &receipt));
+
+        let caller = self.get_caller();
+        let is_owner = caller == self.get_owner_address();
+        let mut slash_votes = self.get_slash_votes(&relayer, &poly_tx_hash);
+
+        if !is_owner {
+            require!(
+                self.is_active_relayer(&caller),
+                "Caller is not a bonded relayer"
+            );
+            require!(caller != relayer, "Relayer may not vote on its own slash");
+            require!(!self.is_empty_slash_quorum(), "slash quorum not set");
+            require!(!slash_votes.contains(&caller), "Already voted");
+        }
+
+        self.verify_inbound_tx(from_chain_id, height, &receipt.hash, &audit_path);
+
+        if is_owner {
+            self.perform_slash(&relayer, &poly_tx_hash);
+
+            return Ok(());
+        }
+
+        slash_votes.push(caller);
+
+        if slash_votes.len() >= self.get_slash_votes_needed(&relayer) {
+            self.perform_slash(&relayer, &poly_tx_hash);
+        } else {
+            self.set_slash_votes(&relayer, &poly_tx_hash, &slash_votes);
+        }
+
+        Ok(())
+    }
+
     // endpoints - token manager contract only
 
     #[endpoint(completeTx)]
@@ -237,6 +668,7 @@ pub trait CrossChainManagement {
             approved_address_list.contains(&self.get_caller()),
             "Caller is not an approved address"
         );
+        require!(!self.is_outbound_paused(), "Outbound transfers are paused");
 
         require!(
             !self.is_empty_tx_by_hash(&poly_tx_hash),
@@ -246,6 +678,16 @@ pub trait CrossChainManagement {
             self.get_tx_status(&poly_tx_hash) == TransactionStatus::Pending,
             "Transaction must be in Pending status"
         );
+        require!(
+            !self.is_chain_paused(self.get_tx_by_hash(&poly_tx_hash).to_chain_id),
+            "Transfers to this chain are paused"
+        );
+        if !self.is_empty_payment_for_tx(&poly_tx_hash) {
+            require!(
+                !self.is_token_paused(&self.get_payment_for_tx(&poly_tx_hash).token_identifier),
+                "Transfers of this token are paused"
+            );
+        }
 
         if tx_status == TransactionStatus::Executed {
             self.add_tx_payment_to_burn_list(&poly_tx_hash);
@@ -256,6 +698,7 @@ pub trait CrossChainManagement {
         }
 
         self.set_tx_status(&poly_tx_hash, tx_status);
+        self.set_tx_status_voter(&poly_tx_hash, &self.get_caller());
 
         Ok(())
     }
@@ -281,6 +724,8 @@ pub trait CrossChainManagement {
             to_chain_id != self.get_own_chain_id(),
             "Must send to a chain other than Elrond"
         );
+        require!(!self.is_outbound_paused(), "Outbound transfers are paused");
+        require!(!self.is_chain_paused(to_chain_id), "Transfers to this chain are paused");
 
         let tx_id = self.get_cross_chain_tx_id(to_chain_id);
         let from_contract_address = self.get_caller();
@@ -293,7 +738,7 @@ pub trait CrossChainManagement {
             method_name,
             method_args,
         };
-        tx.hash = self.hash_transaction(&tx);
+        tx.hash = sc_try!(self.hash_transaction(&tx));
 
         if token_identifier.is_esdt() && esdt_value > 0 {
             let token_whitelist = self.get_token_whitelist();
@@ -302,6 +747,30 @@ pub trait CrossChainManagement {
                 token_whitelist.contains(&token_identifier),
                 "Token is not on whitelist. Transaction rejected"
             );
+            require!(
+                !self.is_token_paused(&token_identifier),
+                "Transfers of this token are paused"
+            );
+
+            // the bridge fee funds the relayer rewards, only the rest is transferred
+            let bridge_fee = self.get_bridge_fee(&token_identifier);
+            require!(esdt_value > bridge_fee, "Payment must be more than the bridge fee");
+
+            let amount = esdt_value - bridge_fee.clone();
+            require!(
+                self.try_add_transfer_volume(
+                    &token_identifier,
+                    to_chain_id,
+                    TransferDirection::Outbound,
+                    &amount
+                ),
+                "Transfer limits exceeded. Transaction rejected"
+            );
+
+            if bridge_fee > 0 {
+                self.relayer_rewards()
+                    .add_reward_funds(&token_identifier, &bridge_fee);
+            }
 
             self.set_payment_for_tx(
                 &tx.hash,
@@ -309,7 +778,7 @@ pub trait CrossChainManagement {
                     sender: from_contract_address,
                     receiver: to_contract_address,
                     token_identifier,
-                    amount: esdt_value,
+                    amount,
                 },
             );
         }
@@ -324,15 +793,18 @@ pub trait CrossChainManagement {
         Ok(())
     }
 
+    /// _audit_path_ proves the transaction's hash against the cross state root of the header at _height_.
+    /// _token_identifier_ and _amount_ must match the payment in the transaction's arguments.
     #[endpoint(processCrossChainTx)]
     fn process_cross_chain_tx(
         &self,
         from_chain_id: u64,
         height: u32,
         tx: Transaction,
+        audit_path: BoxedBytes,
         token_identifier: TokenIdentifier,
         amount: BigUint,
-    ) -> SCResult<()> {
+    ) -> SCResult<TransactionStatus> {
         require!(
             !self.is_empty_token_management_contract_address(),
             "token management contract address not set"
@@ -342,11 +814,17 @@ pub trait CrossChainManagement {
             self.get_own_chain_id() == tx.to_chain_id,
             "This transaction is meant for another chain"
         );
+        require!(!self.is_inbound_paused(), "Inbound transfers are paused");
+        require!(
+            !self.is_chain_paused(from_chain_id),
+            "Transfers from this chain are paused"
+        );
 
         require!(
-            tx.hash == self.hash_transaction(&tx),
+            tx.hash == sc_try!(self.hash_transaction(&tx)),
             "Wrong transaction hash"
         );
+        sc_try!(self.check_tx_payment(&tx, &token_identifier, &amount));
 
         require!(
             self.is_empty_tx_by_hash(&tx.hash),
@@ -360,13 +838,53 @@ pub trait CrossChainManagement {
                 token_whitelist.contains(&token_identifier),
                 "Token is not on whitelist. Transaction rejected"
             );
+            require!(
+                !self.is_token_paused(&token_identifier),
+                "Transfers of this token are paused"
+            );
         }
 
-        let contract_address = self.get_header_sync_contract_address();
-        let proxy = contract_proxy!(self, &contract_address, BlockHeaderSync);
-        proxy.getHeaderByHeight(from_chain_id, height, &tx, &token_identifier, &amount);
+        self.verify_inbound_tx(from_chain_id, height, &tx.hash, &audit_path);
 
-        Ok(())
+        self.set_tx_by_hash(&tx.hash, &tx);
+        self.set_tx_source_chain_id(&tx.hash, from_chain_id);
+
+        // TODO: Add transactions to a list
+
+        // transfers over the limits are queued until released by the owner
+        let status = if token_identifier.is_esdt()
+            && amount > 0
+            && !self.try_add_transfer_volume(
+                &token_identifier,
+                from_chain_id,
+                TransferDirection::Inbound,
+                &amount,
+            ) {
+            let mut queued_tx_list = self.get_queued_tx_list();
+            queued_tx_list.push(tx.hash.clone());
+            self.set_queued_tx_list(&queued_tx_list);
+
+            TransactionStatus::Queued
+        } else {
+            TransactionStatus::Pending
+        };
+        self.set_tx_status(&tx.hash, status);
+
+        if token_identifier.is_esdt() && amount > 0 {
+            self.set_tx_relayer(&tx.hash, &self.get_caller());
+
+            self.set_payment_for_tx(
+                &tx.hash,
+                &EsdtPayment {
+                    sender: tx.from_contract_address,
+                    receiver: tx.to_contract_address,
+                    token_identifier,
+                    amount,
+                },
+            );
+        }
+
+        Ok(status)
     }
 
     #[endpoint(processPendingTx)]
@@ -376,7 +894,21 @@ pub trait CrossChainManagement {
             "Transaction is not in Pending status"
         );
 
-        self.process_tx(&poly_tx_hash)
+        sc_try!(self.process_tx(&poly_tx_hash));
+
+        // the relayer that proved the transaction is only rewarded once it's executed
+        if !self.is_empty_payment_for_tx(&poly_tx_hash) {
+            let token_identifier = self.get_payment_for_tx(&poly_tx_hash).token_identifier;
+
+            let relay_reward = self.get_process_cross_chain_tx_reward(&token_identifier);
+            self.credit_relayer_reward(&self.get_tx_relayer(&poly_tx_hash), &token_identifier, &relay_reward);
+            self.clear_tx_relayer(&poly_tx_hash);
+
+            let reward = self.get_process_pending_tx_reward(&token_identifier);
+            self.credit_relayer_reward(&self.get_caller(), &token_identifier, &reward);
+        }
+
+        Ok(())
     }
 
     #[endpoint(retryOutOfFundsTx)]
@@ -416,59 +948,39 @@ pub trait CrossChainManagement {
         }
     }
 
-    // callbacks
-
-    #[callback]
-    fn get_header_by_height_callback(
+    /// None if there are no limits set for this route
+    #[view(getRemainingCapacity)]
+    fn get_remaining_capacity(
         &self,
-        result: AsyncCallResult<Option<Header>>,
-        #[callback_arg] tx: Transaction,
-        #[callback_arg] token_identifier: TokenIdentifier,
-        #[callback_arg] amount: BigUint,
-    ) {
-        match result {
-            AsyncCallResult::Ok(opt_header) => {
-                match opt_header {
-                    Some(_header) => {
-                        // if this is not empty, it means processCrossChainTx was called more than once with the same tx
-                        // so this should not be executed again
-                        if !self.is_empty_tx_by_hash(&tx.hash) {
-                            return;
-                        }
-
-                        // TODO: check tx proof
-
-                        self.set_tx_by_hash(&tx.hash, &tx);
-                        self.set_tx_status(&tx.hash, TransactionStatus::Pending);
+        token_identifier: TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    ) -> Option<BigUint> {
+        if self.is_empty_transfer_limits(&token_identifier, chain_id, direction) {
+            return None;
+        }
 
-                        // TODO: Add transactions to a list
+        let limits = self.get_transfer_limits(&token_identifier, chain_id, direction);
+        let transfer_volume =
+            self.get_current_transfer_volume(&token_identifier, chain_id, direction, limits.period_seconds);
+        let used_volume = self.get_window_volume(&transfer_volume, limits.period_seconds);
 
-                        if token_identifier.is_esdt() && amount > 0 {
-                            self.set_payment_for_tx(
-                                &tx.hash,
-                                &EsdtPayment {
-                                    sender: tx.from_contract_address,
-                                    receiver: tx.to_contract_address,
-                                    token_identifier,
-                                    amount,
-                                },
-                            );
-                        }
-                    }
-                    None => {
-                        // could not find header
-                        // should sync header first
-                    }
-                };
-            }
-            AsyncCallResult::Err(_) => {}
+        if used_volume < limits.max_volume_per_period {
+            Some(limits.max_volume_per_period - used_volume)
+        } else {
+            Some(BigUint::zero())
         }
     }
 
     // private
 
-    fn hash_transaction(&self, tx: &Transaction) -> H256 {
-        self.sha256(tx.get_partial_serialized().as_slice())
+    fn hash_transaction(&self, tx: &Transaction) -> SCResult<H256> {
+        let serialized = match tx.get_partial_serialized() {
+            core::result::Result::Ok(serialized) => serialized,
+            core::result::Result::Err(err) => return Err(SCError::from(err))
+        };
+
+        Ok(self.sha256(serialized.as_slice()))
     }
 
     fn save_tx_to_pending_list(&self, poly_tx_hash: &H256) {
@@ -489,6 +1001,18 @@ pub trait CrossChainManagement {
             !self.is_empty_tx_by_hash(poly_tx_hash),
             "Transaction does not exist"
         );
+        require!(!self.is_inbound_paused(), "Inbound transfers are paused");
+        require!(
+            !self.is_empty_tx_source_chain_id(poly_tx_hash),
+            "Transaction is not an inbound transaction"
+        );
+
+        require!(
+            !self.is_chain_paused(self.get_tx_source_chain_id(poly_tx_hash)),
+            "Transfers from this chain are paused"
+        );
+
+        self.check_source_chain_trusted(poly_tx_hash);
 
         let tx = self.get_tx_by_hash(poly_tx_hash);
 
@@ -498,6 +1022,11 @@ pub trait CrossChainManagement {
         let esdt_payment = self.get_payment_for_tx(poly_tx_hash);
         let token_management_contract_address = self.get_token_management_contract_address();
 
+        require!(
+            !self.is_token_paused(&esdt_payment.token_identifier),
+            "Transfers of this token are paused"
+        );
+
         let mut serializer = HexCallDataSerializer::new(TRANSFER_ESDT_ENDPOINT_NAME);
         serializer.push_argument_bytes(esdt_payment.token_identifier.as_slice());
         serializer.push_argument_bytes(esdt_payment.amount.to_bytes_be().as_slice());
@@ -505,7 +1034,7 @@ pub trait CrossChainManagement {
         serializer.push_argument_bytes(tx.hash.as_bytes());
 
         serializer.push_argument_bytes(tx.method_name.as_slice());
-        for arg in &tx.method_args {
+        for arg in tx.method_args.iter().skip(INBOUND_TX_PAYMENT_ARGS_LEN) {
             serializer.push_argument_bytes(arg.as_slice());
         }
 
@@ -521,6 +1050,38 @@ pub trait CrossChainManagement {
         Ok(())
     }
 
+    // fails, and so reverts the whole transaction, if the header is not synced, the chain can't be trusted
+    // or the transaction is not proven against the header's cross state root
+    fn verify_inbound_tx(&self, from_chain_id: u64, height: u32, poly_tx_hash: &H256, audit_path: &BoxedBytes) {
+        let mut arg_buffer = ArgBuffer::new();
+        arg_buffer.push_raw_arg(&from_chain_id.to_be_bytes()[..]);
+        arg_buffer.push_raw_arg(&height.to_be_bytes()[..]);
+        arg_buffer.push_raw_arg(poly_tx_hash.as_bytes());
+        arg_buffer.push_raw_arg(audit_path.as_slice());
+
+        self.send().execute_on_dest_context(
+            self.get_gas_left(),
+            &self.get_header_sync_contract_address(),
+            &BigUint::zero(),
+            VERIFY_INBOUND_TX_ENDPOINT_NAME,
+            &arg_buffer,
+        );
+    }
+
+    // fails, and so reverts the whole transaction, if the chain the transaction came from is frozen or stale
+    fn check_source_chain_trusted(&self, poly_tx_hash: &H256) {
+        let mut arg_buffer = ArgBuffer::new();
+        arg_buffer.push_raw_arg(&self.get_tx_source_chain_id(poly_tx_hash).to_be_bytes()[..]);
+
+        self.send().execute_on_dest_context(
+            self.get_gas_left(),
+            &self.get_header_sync_contract_address(),
+            &BigUint::zero(),
+            CHECK_CHAIN_TRUSTED_ENDPOINT_NAME,
+            &arg_buffer,
+        );
+    }
+
     fn add_tx_payment_to_burn_list(&self, poly_tx_hash: &H256) {
         if self.is_empty_payment_for_tx(poly_tx_hash) {
             return;
@@ -586,6 +1147,240 @@ pub trait CrossChainManagement {
         self.clear_payment_for_tx(poly_tx_hash);
     }
 
+    // transfer limits
+
+    /// Returns false if the transfer is outside the route's limits, in which case the volume is not updated.
+    /// Routes with no limits set are unrestricted.
+    fn try_add_transfer_volume(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+        amount: &BigUint,
+    ) -> bool {
+        if self.is_empty_transfer_limits(token_identifier, chain_id, direction) {
+            return true;
+        }
+
+        let limits = self.get_transfer_limits(token_identifier, chain_id, direction);
+        if amount < &limits.min_amount_per_tx || amount > &limits.max_amount_per_tx {
+            return false;
+        }
+
+        let mut transfer_volume =
+            self.get_current_transfer_volume(token_identifier, chain_id, direction, limits.period_seconds);
+        let used_volume = self.get_window_volume(&transfer_volume, limits.period_seconds);
+
+        if used_volume + amount.clone() > limits.max_volume_per_period {
+            return false;
+        }
+
+        transfer_volume.volume += amount;
+        self.set_transfer_volume(token_identifier, chain_id, direction, &transfer_volume);
+
+        true
+    }
+
+    /// The route's volume, moved forward to the period the current timestamp is in
+    fn get_current_transfer_volume(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+        period_seconds: u64,
+    ) -> TransferVolume<BigUint> {
+        let current_timestamp = self.get_block_timestamp();
+        let new_transfer_volume = TransferVolume {
+            period_start: current_timestamp,
+            volume: BigUint::zero(),
+            previous_volume: BigUint::zero(),
+        };
+
+        if self.is_empty_transfer_volume(token_identifier, chain_id, direction) {
+            return new_transfer_volume;
+        }
+
+        let transfer_volume = self.get_transfer_volume(token_identifier, chain_id, direction);
+        let elapsed_periods =
+            current_timestamp.saturating_sub(transfer_volume.period_start) / period_seconds;
+
+        match elapsed_periods {
+            0 => transfer_volume,
+            1 => TransferVolume {
+                period_start: transfer_volume.period_start + period_seconds,
+                volume: BigUint::zero(),
+                previous_volume: transfer_volume.volume,
+            },
+            _ => new_transfer_volume,
+        }
+    }
+
+    // rounded up, so the previous period's volume is never underestimated
+    fn get_window_volume(&self, transfer_volume: &TransferVolume<BigUint>, period_seconds: u64) -> BigUint {
+        let elapsed_seconds = self.get_block_timestamp().saturating_sub(transfer_volume.period_start);
+        let previous_period_weight = period_seconds.saturating_sub(elapsed_seconds);
+
+        let weighted_previous_volume = (transfer_volume.previous_volume.clone()
+            * BigUint::from(previous_period_weight)
+            + BigUint::from(period_seconds - 1))
+            / BigUint::from(period_seconds);
+
+        weighted_previous_volume + transfer_volume.volume.clone()
+    }
+
+    // anyone may relay proven transactions, but only approved relayers are rewarded, from the pool of the token transferred
+    fn credit_relayer_reward(&self, relayer: &Address, token_identifier: &TokenIdentifier, reward: &BigUint) {
+        if !self.get_approved_address_list().contains(relayer) {
+            return;
+        }
+
+        self.relayer_rewards()
+            .credit_relayer_reward(relayer, token_identifier, reward);
+    }
+
+    fn add_address_to_approved_list_if_missing(&self, address: &Address) {
+        let mut approved_address_list = self.get_approved_address_list();
+
+        if !approved_address_list.contains(address) {
+            approved_address_list.push(address.clone());
+
+            self.set_approved_address_list(&approved_address_list);
+            self.set_header_sync_approved_relayer(address, true);
+        }
+    }
+
+    fn remove_address_from_approved_list_if_present(&self, address: &Address) {
+        let mut approved_address_list = self.get_approved_address_list();
+
+        if let Some(index) = approved_address_list.iter().position(|addr| addr == address) {
+            approved_address_list.remove(index);
+
+            self.set_approved_address_list(&approved_address_list);
+            self.set_header_sync_approved_relayer(address, false);
+        }
+    }
+
+    // the header sync contract rewards the same relayers, so it mirrors the approved address list
+    fn set_header_sync_approved_relayer(&self, relayer: &Address, approved: bool) {
+        let mut serializer = HexCallDataSerializer::new(SET_APPROVED_RELAYER_ENDPOINT_NAME);
+        serializer.push_argument_bytes(relayer.as_bytes());
+        serializer.push_argument_bytes(if approved { &[1u8][..] } else { &[][..] });
+
+        // TODO: Replace with async_call without callback
+        self.send().direct_egld(
+            &self.get_header_sync_contract_address(),
+            &BigUint::zero(),
+            serializer.as_slice(),
+        );
+    }
+
+    // the payment is only trusted as part of the proven transaction, not as given by the relayer
+    fn check_tx_payment(
+        &self,
+        tx: &Transaction,
+        token_identifier: &TokenIdentifier,
+        amount: &BigUint,
+    ) -> SCResult<()> {
+        require!(
+            tx.method_args.len() >= INBOUND_TX_PAYMENT_ARGS_LEN,
+            "Transaction arguments must start with its payment"
+        );
+        require!(
+            &TokenIdentifier::from(tx.method_args[0].as_slice()) == token_identifier
+                && &BigUint::from_bytes_be(tx.method_args[1].as_slice()) == amount,
+            "Payment does not match the transaction"
+        );
+
+        Ok(())
+    }
+
+    // only the receipt's hash is proven, the rest is checked here, before the more expensive proof
+    fn check_receipt_contradicts_vote(
+        &self,
+        poly_tx_hash: &H256,
+        from_chain_id: u64,
+        receipt: &Transaction,
+    ) -> SCResult<()> {
+        require!(
+            from_chain_id == self.get_tx_by_hash(poly_tx_hash).to_chain_id,
+            "Receipt must come from the transaction's destination chain"
+        );
+        require!(
+            receipt.hash == sc_try!(self.hash_transaction(receipt)),
+            "Wrong transaction hash"
+        );
+        require!(
+            !self.is_empty_trusted_bridge_contract(from_chain_id)
+                && receipt.from_contract_address == self.get_trusted_bridge_contract(from_chain_id),
+            "Receipt must be sent by the chain's trusted bridge contract"
+        );
+        require!(
+            receipt.to_contract_address == self.get_sc_address(),
+            "Receipt must be sent to this contract"
+        );
+        require!(
+            receipt.to_chain_id == self.get_own_chain_id()
+                && receipt.method_name.as_slice() == TX_STATUS_RECEIPT_METHOD_NAME
+                && receipt.method_args.len() == 2
+                && receipt.method_args[0].as_slice() == poly_tx_hash.as_bytes(),
+            "Not a status receipt for this transaction"
+        );
+
+        let receipt_status = match TransactionStatus::top_decode(receipt.method_args[1].as_slice()) {
+            core::result::Result::Ok(status) => status,
+            core::result::Result::Err(_) => return sc_error!("Invalid receipt status"),
+        };
+        require!(
+            receipt_status == TransactionStatus::Executed
+                || receipt_status == TransactionStatus::Rejected,
+            "Invalid receipt status"
+        );
+        require!(
+            receipt_status != self.get_tx_status(poly_tx_hash),
+            "Receipt does not contradict the relayer's vote"
+        );
+
+        Ok(())
+    }
+
+    // the slashed relayer doesn't count towards the quorum, as it may not vote
+    fn get_slash_votes_needed(&self, relayer: &Address) -> usize {
+        let mut voters_count = self.get_active_relayer_count();
+        if self.is_active_relayer(relayer) {
+            voters_count -= 1;
+        }
+
+        let votes_needed = (voters_count * self.get_slash_quorum()).div_ceil(100);
+        core::cmp::max(votes_needed, 1)
+    }
+
+    // bonded relayers that are not unbonding, the only ones that may vote on slashes
+    fn is_active_relayer(&self, address: &Address) -> bool {
+        self.get_relayer_bond(address) > 0 && self.is_empty_relayer_unbond_start_block(address)
+    }
+
+    // the whole bond is slashed, including a bond that is currently unbonding
+    fn perform_slash(&self, relayer: &Address, poly_tx_hash: &H256) {
+        let bond = self.get_relayer_bond(relayer);
+
+        if self.is_active_relayer(relayer) {
+            self.set_active_relayer_count(self.get_active_relayer_count() - 1);
+        }
+
+        self.remove_address_from_approved_list_if_present(relayer);
+        self.clear_relayer_bond(relayer);
+        self.clear_relayer_unbond_start_block(relayer);
+        self.clear_slash_votes(relayer, poly_tx_hash);
+
+        self.send().direct_egld(
+            &self.get_insurance_pool_address(),
+            &bond,
+            b"relayer slashed",
+        );
+
+        self.relayer_slashed_event(relayer, poly_tx_hash, &bond);
+    }
+
     fn burn_esdt_token(&self, token_identifier: &TokenIdentifier, amount: &BigUint) {
         let mut serializer = HexCallDataSerializer::new(ESDT_BURN_STRING);
         serializer.push_argument_bytes(token_identifier.as_slice());
@@ -617,6 +1412,9 @@ pub trait CrossChainManagement {
     #[event("0x1000000000000000000000000000000000000000000000000000000000000001")]
     fn create_tx_event(&self, tx: &Transaction);
 
+    #[event("0x1000000000000000000000000000000000000000000000000000000000000002")]
+    fn relayer_slashed_event(&self, relayer: &Address, poly_tx_hash: &H256, amount: &BigUint);
+
     // storage TODO: Use storage mappers
 
     // header sync contract address
@@ -738,6 +1536,29 @@ pub trait CrossChainManagement {
     #[storage_is_empty("txByHash")]
     fn is_empty_tx_by_hash(&self, poly_tx_hash: &H256) -> bool;
 
+    // the chain an inbound transaction came from, empty for outbound transactions
+
+    #[view(getTxSourceChainId)]
+    #[storage_get("txSourceChainId")]
+    fn get_tx_source_chain_id(&self, poly_tx_hash: &H256) -> u64;
+
+    #[storage_set("txSourceChainId")]
+    fn set_tx_source_chain_id(&self, poly_tx_hash: &H256, chain_id: u64);
+
+    #[storage_is_empty("txSourceChainId")]
+    fn is_empty_tx_source_chain_id(&self, poly_tx_hash: &H256) -> bool;
+
+    // the relayer that processed an inbound transaction with a payment, rewarded once the transaction is executed
+
+    #[storage_get("txRelayer")]
+    fn get_tx_relayer(&self, poly_tx_hash: &H256) -> Address;
+
+    #[storage_set("txRelayer")]
+    fn set_tx_relayer(&self, poly_tx_hash: &H256, relayer: &Address);
+
+    #[storage_clear("txRelayer")]
+    fn clear_tx_relayer(&self, poly_tx_hash: &H256);
+
     // list of hashes for pending tx from elrond to another chain
 
     #[storage_get("pendingCrosschainTxList")]
@@ -784,4 +1605,250 @@ pub trait CrossChainManagement {
 
     #[storage_set("approvedAddressList")]
     fn set_approved_address_list(&self, approved_address_list: &[Address]);
+
+    // Relayer registry - relayers bond eGLD to join the approved address list
+
+    #[view(getRelayerMinBond)]
+    #[storage_get("relayerMinBond")]
+    fn get_relayer_min_bond(&self) -> BigUint;
+
+    #[storage_set("relayerMinBond")]
+    fn set_relayer_min_bond(&self, min_bond: &BigUint);
+
+    #[storage_is_empty("relayerMinBond")]
+    fn is_empty_relayer_min_bond(&self) -> bool;
+
+    // number of blocks between unbondRelayer and withdrawRelayerBond
+
+    #[view(getRelayerUnbondPeriod)]
+    #[storage_get("relayerUnbondPeriod")]
+    fn get_relayer_unbond_period(&self) -> u64;
+
+    #[storage_set("relayerUnbondPeriod")]
+    fn set_relayer_unbond_period(&self, unbond_period: u64);
+
+    #[view(getRelayerBond)]
+    #[storage_get("relayerBond")]
+    fn get_relayer_bond(&self, relayer: &Address) -> BigUint;
+
+    #[storage_set("relayerBond")]
+    fn set_relayer_bond(&self, relayer: &Address, bond: &BigUint);
+
+    #[storage_clear("relayerBond")]
+    fn clear_relayer_bond(&self, relayer: &Address);
+
+    #[view(getRelayerUnbondStartBlock)]
+    #[storage_get("relayerUnbondStartBlock")]
+    fn get_relayer_unbond_start_block(&self, relayer: &Address) -> u64;
+
+    #[storage_set("relayerUnbondStartBlock")]
+    fn set_relayer_unbond_start_block(&self, relayer: &Address, block_nonce: u64);
+
+    #[storage_clear("relayerUnbondStartBlock")]
+    fn clear_relayer_unbond_start_block(&self, relayer: &Address);
+
+    #[storage_is_empty("relayerUnbondStartBlock")]
+    fn is_empty_relayer_unbond_start_block(&self, relayer: &Address) -> bool;
+
+    // slashing - slashed bonds are sent to the insurance pool
+
+    #[view(getInsurancePoolAddress)]
+    #[storage_get("insurancePoolAddress")]
+    fn get_insurance_pool_address(&self) -> Address;
+
+    #[storage_set("insurancePoolAddress")]
+    fn set_insurance_pool_address(&self, address: &Address);
+
+    #[storage_is_empty("insurancePoolAddress")]
+    fn is_empty_insurance_pool_address(&self) -> bool;
+
+    // the bridge contract on each chain, the only sender of that chain's status receipts
+
+    #[view(getTrustedBridgeContract)]
+    #[storage_get("trustedBridgeContract")]
+    fn get_trusted_bridge_contract(&self, chain_id: u64) -> Address;
+
+    #[storage_set("trustedBridgeContract")]
+    fn set_trusted_bridge_contract(&self, chain_id: u64, contract_address: &Address);
+
+    #[storage_is_empty("trustedBridgeContract")]
+    fn is_empty_trusted_bridge_contract(&self, chain_id: u64) -> bool;
+
+    // percentage of the other bonded relayers that must vote for a slash
+
+    #[view(getSlashQuorum)]
+    #[storage_get("slashQuorum")]
+    fn get_slash_quorum(&self) -> usize;
+
+    #[storage_set("slashQuorum")]
+    fn set_slash_quorum(&self, quorum_percentage: usize);
+
+    #[storage_is_empty("slashQuorum")]
+    fn is_empty_slash_quorum(&self) -> bool;
+
+    #[view(getActiveRelayerCount)]
+    #[storage_get("activeRelayerCount")]
+    fn get_active_relayer_count(&self) -> usize;
+
+    #[storage_set("activeRelayerCount")]
+    fn set_active_relayer_count(&self, count: usize);
+
+    #[view(getSlashVotes)]
+    #[storage_get("slashVotes")]
+    fn get_slash_votes(&self, relayer: &Address, poly_tx_hash: &H256) -> Vec<Address>;
+
+    #[storage_set("slashVotes")]
+    fn set_slash_votes(&self, relayer: &Address, poly_tx_hash: &H256, voters: &[Address]);
+
+    #[storage_clear("slashVotes")]
+    fn clear_slash_votes(&self, relayer: &Address, poly_tx_hash: &H256);
+
+    // the approved address that set the status of an outgoing transaction through setOffchainTxStatus
+
+    #[view(getTxStatusVoter)]
+    #[storage_get("txStatusVoter")]
+    fn get_tx_status_voter(&self, poly_tx_hash: &H256) -> Address;
+
+    #[storage_set("txStatusVoter")]
+    fn set_tx_status_voter(&self, poly_tx_hash: &H256, voter: &Address);
+
+    #[storage_is_empty("txStatusVoter")]
+    fn is_empty_tx_status_voter(&self, poly_tx_hash: &H256) -> bool;
+
+    // Relayer rewards - paid from the pool of each token, funded with the bridge fees of outbound transfers
+
+    #[module(RelayerRewardsModuleImpl)]
+    fn relayer_rewards(&self) -> RelayerRewardsModuleImpl<T, BigInt, BigUint>;
+
+    #[view(getBridgeFee)]
+    #[storage_get("bridgeFee")]
+    fn get_bridge_fee(&self, token_identifier: &TokenIdentifier) -> BigUint;
+
+    #[storage_set("bridgeFee")]
+    fn set_bridge_fee(&self, token_identifier: &TokenIdentifier, fee: &BigUint);
+
+    #[view(getProcessCrossChainTxReward)]
+    #[storage_get("processCrossChainTxReward")]
+    fn get_process_cross_chain_tx_reward(&self, token_identifier: &TokenIdentifier) -> BigUint;
+
+    #[storage_set("processCrossChainTxReward")]
+    fn set_process_cross_chain_tx_reward(&self, token_identifier: &TokenIdentifier, reward: &BigUint);
+
+    #[view(getProcessPendingTxReward)]
+    #[storage_get("processPendingTxReward")]
+    fn get_process_pending_tx_reward(&self, token_identifier: &TokenIdentifier) -> BigUint;
+
+    #[storage_set("processPendingTxReward")]
+    fn set_process_pending_tx_reward(&self, token_identifier: &TokenIdentifier, reward: &BigUint);
+
+    // Transfer limits - per (token, chain, direction) route
+
+    #[view(getTransferLimits)]
+    #[storage_get("transferLimits")]
+    fn get_transfer_limits(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    ) -> TransferLimits<BigUint>;
+
+    #[storage_set("transferLimits")]
+    fn set_transfer_limits(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+        limits: &TransferLimits<BigUint>,
+    );
+
+    #[storage_clear("transferLimits")]
+    fn clear_transfer_limits(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    );
+
+    #[storage_is_empty("transferLimits")]
+    fn is_empty_transfer_limits(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    ) -> bool;
+
+    #[storage_get("transferVolume")]
+    fn get_transfer_volume(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    ) -> TransferVolume<BigUint>;
+
+    #[storage_set("transferVolume")]
+    fn set_transfer_volume(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+        volume: &TransferVolume<BigUint>,
+    );
+
+    #[storage_clear("transferVolume")]
+    fn clear_transfer_volume(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    );
+
+    #[storage_is_empty("transferVolume")]
+    fn is_empty_transfer_volume(
+        &self,
+        token_identifier: &TokenIdentifier,
+        chain_id: u64,
+        direction: TransferDirection,
+    ) -> bool;
+
+    // inbound transactions that exceeded the transfer limits, waiting to be released by the owner
+
+    #[view(getQueuedTxList)]
+    #[storage_get("queuedTxList")]
+    fn get_queued_tx_list(&self) -> Vec<H256>;
+
+    #[storage_set("queuedTxList")]
+    fn set_queued_tx_list(&self, queued_tx_list: &[H256]);
+
+    // pause - the guardian may pause, but only the owner may unpause
+
+    #[module(PauseModuleImpl)]
+    fn pause(&self) -> PauseModuleImpl<T, BigInt, BigUint>;
+
+    #[view(isOutboundPaused)]
+    #[storage_get("outboundPaused")]
+    fn is_outbound_paused(&self) -> bool;
+
+    #[storage_set("outboundPaused")]
+    fn set_outbound_paused(&self, paused: bool);
+
+    #[view(isInboundPaused)]
+    #[storage_get("inboundPaused")]
+    fn is_inbound_paused(&self) -> bool;
+
+    #[storage_set("inboundPaused")]
+    fn set_inbound_paused(&self, paused: bool);
+
+    #[view(isTokenPaused)]
+    #[storage_get("tokenPaused")]
+    fn is_token_paused(&self, token_identifier: &TokenIdentifier) -> bool;
+
+    #[storage_set("tokenPaused")]
+    fn set_token_paused(&self, token_identifier: &TokenIdentifier, paused: bool);
+
+    #[view(isChainPaused)]
+    #[storage_get("chainPaused")]
+    fn is_chain_paused(&self, chain_id: u64) -> bool;
+
+    #[storage_set("chainPaused")]
+    fn set_chain_paused(&self, chain_id: u64, paused: bool);
 }
@@ -3,6 +3,7 @@
 
 use elrond_wasm::{derive_imports, imports, HexCallDataSerializer};
 
+use pause::*;
 use transaction::TransactionStatus;
 
 imports!();
@@ -139,6 +140,26 @@ pub trait EsdtTokenManager {
         Ok(())
     }
 
+    #[endpoint(unpauseWrapping)]
+    fn unpause_wrapping(&self) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_wrapping_paused(false);
+
+        Ok(())
+    }
+
+    // endpoints - guardian or owner
+
+    #[endpoint(pauseWrapping)]
+    fn pause_wrapping(&self) -> SCResult<()> {
+        sc_try!(self.pause().require_guardian_or_owner());
+
+        self.set_wrapping_paused(true);
+
+        Ok(())
+    }
+
     // endpoints - CrossChainManagement contract - only
 
     #[endpoint(transferEsdt)]
@@ -181,6 +202,7 @@ pub trait EsdtTokenManager {
     #[payable("EGLD")]
     #[endpoint(wrapEgld)]
     fn wrap_egld(&self, #[payment] payment: BigUint) -> SCResult<()> {
+        require!(!self.is_wrapping_paused(), "Wrapping and unwrapping are paused");
         require!(payment > 0, "Payment must be more than 0");
 
         require!(
@@ -214,6 +236,7 @@ pub trait EsdtTokenManager {
         #[payment] wrapped_egld_payment: BigUint,
         #[payment_token] token_identifier: TokenIdentifier,
     ) -> SCResult<()> {
+        require!(!self.is_wrapping_paused(), "Wrapping and unwrapping are paused");
         require!(
             !self.is_empty_wrapped_egld_token_identifier(),
             "Wrapped eGLD was not issued yet"
@@ -557,6 +580,18 @@ pub trait EsdtTokenManager {
     #[storage_set("crossChainManagementContractAddress")]
     fn set_cross_chain_management_contract_address(&self, address: &Address);
 
+    // pause - the guardian may pause, but only the owner may unpause
+
+    #[module(PauseModuleImpl)]
+    fn pause(&self) -> PauseModuleImpl<T, BigInt, BigUint>;
+
+    #[view(isWrappingPaused)]
+    #[storage_get("wrappingPaused")]
+    fn is_wrapping_paused(&self) -> bool;
+
+    #[storage_set("wrappingPaused")]
+    fn set_wrapping_paused(&self, paused: bool);
+
     // ---------- Temporary storage for raw callbacks ----------
 
     // temporary storage for the poly_tx_hash, which is NOT the same as original_tx_hash
@@ -1,83 +1,38 @@
 #![no_std]
 
-use elrond_wasm::elrond_codec::*;
 use elrond_wasm::{derive_imports, Address, api::BigUintApi, TokenIdentifier};
 
-use zero_copy_sink::*;
-use zero_copy_source::*;
+use zero_copy_derive::*;
 
 derive_imports!();
 
-#[derive(TypeAbi)]
+#[derive(ZeroCopyEncode, ZeroCopyDecode, TypeAbi)]
 pub struct EsdtPayment<BigUint: BigUintApi> {
     pub sender: Address,
     pub receiver: Address,
     pub token_identifier: TokenIdentifier,
+    #[zero_copy(with = "big_uint_var_bytes")]
     pub amount: BigUint,
 }
 
-impl<BigUint: BigUintApi> NestedEncode for EsdtPayment<BigUint> {
-    fn dep_encode<O: NestedEncodeOutput>(&self, dest: &mut O) -> Result<(), EncodeError> {
-        let mut sink = ZeroCopySink::new();
+// big endian bytes, prefixed by their length
+mod big_uint_var_bytes {
+    use elrond_wasm::api::BigUintApi;
+    use elrond_wasm::elrond_codec::*;
 
-        sink.write_address(&self.sender);
-        sink.write_address(&self.receiver);
-        sink.write_var_bytes(self.token_identifier.as_slice());
-        sink.write_var_bytes(self.amount.to_bytes_be().as_slice());
+    use zero_copy_sink::*;
+    use zero_copy_source::*;
 
-        dest.write(sink.get_sink().as_slice());
+    pub fn encode_to_sink<BigUint: BigUintApi, O: NestedEncodeOutput>(
+        amount: &BigUint,
+        sink: &mut O,
+    ) -> Result<(), EncodeError> {
+        sink.write_var_bytes(amount.to_bytes_be().as_slice());
 
         Ok(())
     }
-}
-
-impl<BigUint: BigUintApi> NestedDecode for EsdtPayment<BigUint> {
-    fn dep_decode<I: NestedDecodeInput>(input: &mut I) -> Result<Self, DecodeError> {
-        let mut source = ZeroCopySource::new(input.flush());
-
-        let sender;
-        let receiver;
-        let token_identifier;
-        let amount;
-
-        match source.next_address() {
-            Some(val) => sender = val,
-            None => return Err(DecodeError::INPUT_TOO_SHORT),
-        };
-
-        match source.next_address() {
-            Some(val) => receiver = val,
-            None => return Err(DecodeError::INPUT_TOO_SHORT),
-        };
-
-        match source.next_var_bytes() {
-            Some(val) => token_identifier = val,
-            None => return Err(DecodeError::INPUT_TOO_SHORT),
-        };
-
-        match source.next_var_bytes() {
-            Some(val) => amount = BigUint::from_bytes_be(val.as_slice()),
-            None => return Err(DecodeError::INPUT_TOO_SHORT),
-        };
-
-        return Ok(EsdtPayment {
-            sender,
-            receiver,
-            token_identifier: TokenIdentifier::from(token_identifier),
-            amount,
-        });
-    }
-}
-
-impl<BigUint: BigUintApi> TopEncode for EsdtPayment<BigUint> {
-    #[inline]
-    fn top_encode<O: TopEncodeOutput>(&self, output: O) -> Result<(), EncodeError> {
-        top_encode_from_nested(self, output)
-    }
-}
 
-impl<BigUint: BigUintApi> TopDecode for EsdtPayment<BigUint> {
-    fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
-        top_decode_from_nested(input)
+    pub fn decode_from_source<BigUint: BigUintApi, S: ZeroCopyRead>(source: &mut S) -> Result<BigUint, DecodeError> {
+        source.read_var_bytes().map(BigUint::from_bytes_be)
     }
 }
@@ -0,0 +1,496 @@
+//! A header's consensus payload, as Poly's Go node serializes it: var bytes holding the JSON encoding of its VbftBlockInfo,
+//! or empty var bytes if it has none.
+//! The payload is part of what gets hashed and signed, so only the JSON Go's json.Marshal produces is accepted,
+//! i.e. fields in declaration order, no whitespace, and empty byte strings and lists written as null, as Go writes nil slices.
+//! Anything else is rejected, so a decoded payload encodes back to the bytes that were signed.
+
+use elrond_wasm::{BoxedBytes, Vec};
+use elrond_wasm::elrond_codec::*;
+
+use zero_copy_sink::*;
+use zero_copy_source::*;
+
+use super::chain_config::*;
+use super::peer_config::*;
+use super::vbft_block_info::*;
+
+pub const INVALID_CONSENSUS_PAYLOAD_ERR_MSG: &[u8] = b"invalid consensus payload";
+pub const UNSUPPORTED_PEER_ID_ERR_MSG: &[u8] = b"peer ids must be printable ASCII";
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_PADDING: u8 = b'=';
+
+// time.Duration is an int64
+const MAX_DURATION: u64 = i64::MAX as u64;
+
+pub fn encode_to_sink<O: NestedEncodeOutput>(payload: &Option<VbftBlockInfo>, sink: &mut O) -> Result<(), EncodeError> {
+    match payload {
+        Some(block_info) => sink.write_var_bytes(&to_json(block_info)?),
+        None => sink.write_var_bytes(&[])
+    }
+
+    Ok(())
+}
+
+pub fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Option<VbftBlockInfo>, DecodeError> {
+    let json = source.read_var_bytes()?;
+    if json.is_empty() {
+        return Ok(None);
+    }
+
+    from_json(json).map(Some)
+}
+
+/// The JSON Go writes for _block_info_
+pub fn to_json(block_info: &VbftBlockInfo) -> Result<Vec<u8>, EncodeError> {
+    let mut json = JsonWriter::new();
+
+    json.begin_object();
+    json.key(b"leader", true);
+    json.number(block_info.proposer as u64);
+    json.key(b"vrf_value", false);
+    json.bytes(block_info.vrf_value.as_slice());
+    json.key(b"vrf_proof", false);
+    json.bytes(block_info.vrf_proof.as_slice());
+    json.key(b"last_config_block_num", false);
+    json.number(block_info.last_config_block_num as u64);
+    json.key(b"new_chain_config", false);
+    match &block_info.new_chain_config {
+        Some(chain_config) => write_chain_config(&mut json, chain_config)?,
+        None => json.null()
+    }
+    json.end_object();
+
+    Ok(json.into_vec())
+}
+
+/// Decodes the JSON Go writes for a VbftBlockInfo, see the module docs
+pub fn from_json(json: &[u8]) -> Result<VbftBlockInfo, DecodeError> {
+    let mut reader = JsonReader::new(json);
+
+    reader.expect(b"{")?;
+    reader.key(b"leader", true)?;
+    let proposer = reader.u32()?;
+    reader.key(b"vrf_value", false)?;
+    let vrf_value = reader.bytes()?;
+    reader.key(b"vrf_proof", false)?;
+    let vrf_proof = reader.bytes()?;
+    reader.key(b"last_config_block_num", false)?;
+    let last_config_block_num = reader.u32()?;
+    reader.key(b"new_chain_config", false)?;
+    let new_chain_config = if reader.null()? {
+        None
+    }
+    else {
+        Some(read_chain_config(&mut reader)?)
+    };
+    reader.expect(b"}")?;
+    reader.expect_end()?;
+
+    Ok(VbftBlockInfo {
+        proposer,
+        vrf_value,
+        vrf_proof,
+        last_config_block_num,
+        new_chain_config
+    })
+}
+
+fn write_chain_config(json: &mut JsonWriter, chain_config: &ChainConfig) -> Result<(), EncodeError> {
+    json.begin_object();
+    json.key(b"version", true);
+    json.number(chain_config.version as u64);
+    json.key(b"view", false);
+    json.number(chain_config.view as u64);
+    json.key(b"n", false);
+    json.number(chain_config.network_size as u64);
+    json.key(b"c", false);
+    json.number(chain_config.consensus_quorum as u64);
+    json.key(b"block_msg_delay", false);
+    json.duration(chain_config.block_msg_delay)?;
+    json.key(b"hash_msg_delay", false);
+    json.duration(chain_config.hash_msg_delay)?;
+    json.key(b"peer_handshake_timeout", false);
+    json.duration(chain_config.peer_handshake_timeout)?;
+
+    json.key(b"peers", false);
+    json.begin_list(chain_config.peers.len());
+    for (i, peer) in chain_config.peers.iter().enumerate() {
+        json.list_separator(i);
+        json.begin_object();
+        json.key(b"index", true);
+        json.number(peer.index as u64);
+        json.key(b"id", false);
+        json.string(peer.id.as_slice())?;
+        json.end_object();
+    }
+    json.end_list(chain_config.peers.len());
+
+    json.key(b"pos_table", false);
+    json.begin_list(chain_config.pos_table.len());
+    for (i, position) in chain_config.pos_table.iter().enumerate() {
+        json.list_separator(i);
+        json.number(*position as u64);
+    }
+    json.end_list(chain_config.pos_table.len());
+
+    json.key(b"MaxBlockChangeView", false);
+    json.number(chain_config.max_block_change_view as u64);
+    json.end_object();
+
+    Ok(())
+}
+
+fn read_chain_config(reader: &mut JsonReader) -> Result<ChainConfig, DecodeError> {
+    reader.expect(b"{")?;
+    reader.key(b"version", true)?;
+    let version = reader.u32()?;
+    reader.key(b"view", false)?;
+    let view = reader.u32()?;
+    reader.key(b"n", false)?;
+    let network_size = reader.u32()?;
+    reader.key(b"c", false)?;
+    let consensus_quorum = reader.u32()?;
+    reader.key(b"block_msg_delay", false)?;
+    let block_msg_delay = reader.duration()?;
+    reader.key(b"hash_msg_delay", false)?;
+    let hash_msg_delay = reader.duration()?;
+    reader.key(b"peer_handshake_timeout", false)?;
+    let peer_handshake_timeout = reader.duration()?;
+
+    reader.key(b"peers", false)?;
+    let peers = reader.list(|reader| {
+        reader.expect(b"{")?;
+        reader.key(b"index", true)?;
+        let index = reader.u32()?;
+        reader.key(b"id", false)?;
+        let id = reader.string()?;
+        reader.expect(b"}")?;
+
+        Ok(PeerConfig { index, id })
+    })?;
+
+    reader.key(b"pos_table", false)?;
+    let pos_table = reader.list(|reader| reader.u32())?;
+
+    reader.key(b"MaxBlockChangeView", false)?;
+    let max_block_change_view = reader.u32()?;
+    reader.expect(b"}")?;
+
+    Ok(ChainConfig {
+        version,
+        view,
+        network_size,
+        consensus_quorum,
+        block_msg_delay,
+        hash_msg_delay,
+        peer_handshake_timeout,
+        peers,
+        pos_table,
+        max_block_change_view
+    })
+}
+
+// Go escapes quotes, backslashes, control characters and <, > and &, so ids containing them would need escaping
+fn is_unescaped_string_char(c: u8) -> bool {
+    (0x20..0x7f).contains(&c) && !matches!(c, b'"' | b'\\' | b'<' | b'>' | b'&')
+}
+
+struct JsonWriter {
+    json: Vec<u8>
+}
+
+impl JsonWriter {
+    fn new() -> Self {
+        JsonWriter {
+            json: Vec::new()
+        }
+    }
+
+    fn into_vec(self) -> Vec<u8> {
+        self.json
+    }
+
+    fn begin_object(&mut self) {
+        self.json.push(b'{');
+    }
+
+    fn end_object(&mut self) {
+        self.json.push(b'}');
+    }
+
+    fn key(&mut self, key: &[u8], is_first: bool) {
+        if !is_first {
+            self.json.push(b',');
+        }
+        self.json.push(b'"');
+        self.json.extend_from_slice(key);
+        self.json.extend_from_slice(b"\":");
+    }
+
+    fn null(&mut self) {
+        self.json.extend_from_slice(b"null");
+    }
+
+    fn number(&mut self, val: u64) {
+        let mut digits = [0u8; 20];
+        let mut start = digits.len();
+        let mut val = val;
+
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (val % 10) as u8;
+            val /= 10;
+
+            if val == 0 {
+                break;
+            }
+        }
+
+        self.json.extend_from_slice(&digits[start..]);
+    }
+
+    fn duration(&mut self, val: u64) -> Result<(), EncodeError> {
+        if val > MAX_DURATION {
+            return Err(EncodeError::from(INVALID_CONSENSUS_PAYLOAD_ERR_MSG));
+        }
+
+        self.number(val);
+
+        Ok(())
+    }
+
+    fn string(&mut self, string: &[u8]) -> Result<(), EncodeError> {
+        if !string.iter().all(|c| is_unescaped_string_char(*c)) {
+            return Err(EncodeError::from(UNSUPPORTED_PEER_ID_ERR_MSG));
+        }
+
+        self.json.push(b'"');
+        self.json.extend_from_slice(string);
+        self.json.push(b'"');
+
+        Ok(())
+    }
+
+    /// Standard base64, with padding
+    fn bytes(&mut self, bytes: &[u8]) {
+        if bytes.is_empty() {
+            self.null();
+            return;
+        }
+
+        self.json.push(b'"');
+        for chunk in bytes.chunks(3) {
+            let b0 = chunk[0] as usize;
+            let b1 = chunk.get(1).map_or(0, |b| *b as usize);
+            let b2 = chunk.get(2).map_or(0, |b| *b as usize);
+
+            self.json.push(BASE64_ALPHABET[b0 >> 2]);
+            self.json.push(BASE64_ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)]);
+            self.json.push(if chunk.len() > 1 { BASE64_ALPHABET[((b1 & 0x0f) << 2) | (b2 >> 6)] } else { BASE64_PADDING });
+            self.json.push(if chunk.len() > 2 { BASE64_ALPHABET[b2 & 0x3f] } else { BASE64_PADDING });
+        }
+        self.json.push(b'"');
+    }
+
+    fn begin_list(&mut self, len: usize) {
+        if len == 0 {
+            self.null();
+        }
+        else {
+            self.json.push(b'[');
+        }
+    }
+
+    fn list_separator(&mut self, index: usize) {
+        if index > 0 {
+            self.json.push(b',');
+        }
+    }
+
+    fn end_list(&mut self, len: usize) {
+        if len > 0 {
+            self.json.push(b']');
+        }
+    }
+}
+
+struct JsonReader<'a> {
+    json: &'a [u8],
+    index: usize
+}
+
+impl<'a> JsonReader<'a> {
+    fn new(json: &'a [u8]) -> Self {
+        JsonReader {
+            json,
+            index: 0
+        }
+    }
+
+    fn invalid() -> DecodeError {
+        DecodeError::from(INVALID_CONSENSUS_PAYLOAD_ERR_MSG)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.json.get(self.index).copied()
+    }
+
+    /// Consumes _token_ if the input continues with it
+    fn next_is(&mut self, token: &[u8]) -> bool {
+        if self.json[self.index..].starts_with(token) {
+            self.index += token.len();
+
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &[u8]) -> Result<(), DecodeError> {
+        if self.next_is(token) {
+            Ok(())
+        }
+        else {
+            Err(Self::invalid())
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), DecodeError> {
+        if self.index == self.json.len() {
+            Ok(())
+        }
+        else {
+            Err(Self::invalid())
+        }
+    }
+
+    fn key(&mut self, key: &[u8], is_first: bool) -> Result<(), DecodeError> {
+        if !is_first {
+            self.expect(b",")?;
+        }
+        self.expect(b"\"")?;
+        self.expect(key)?;
+        self.expect(b"\":")
+    }
+
+    fn null(&mut self) -> Result<bool, DecodeError> {
+        Ok(self.next_is(b"null"))
+    }
+
+    /// Leading zeros are rejected, as Go doesn't write them
+    fn number(&mut self, max: u64) -> Result<u64, DecodeError> {
+        let start = self.index;
+        let mut val = 0u64;
+
+        while let Some(c) = self.peek().filter(u8::is_ascii_digit) {
+            val = val.checked_mul(10)
+                .and_then(|val| val.checked_add((c - b'0') as u64))
+                .filter(|val| *val <= max)
+                .ok_or_else(Self::invalid)?;
+            self.index += 1;
+        }
+
+        let len = self.index - start;
+        if len == 0 || (len > 1 && self.json[start] == b'0') {
+            return Err(Self::invalid());
+        }
+
+        Ok(val)
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        self.number(u32::MAX as u64).map(|val| val as u32)
+    }
+
+    fn duration(&mut self) -> Result<u64, DecodeError> {
+        self.number(MAX_DURATION)
+    }
+
+    fn string(&mut self) -> Result<BoxedBytes, DecodeError> {
+        self.expect(b"\"")?;
+
+        let start = self.index;
+        while self.peek().is_some_and(is_unescaped_string_char) {
+            self.index += 1;
+        }
+        let string = BoxedBytes::from(&self.json[start..self.index]);
+
+        self.expect(b"\"")?;
+
+        Ok(string)
+    }
+
+    /// Only canonical base64 is accepted, i.e. padded, and with the unused bits of the last character set to 0
+    fn bytes(&mut self) -> Result<BoxedBytes, DecodeError> {
+        if self.null()? {
+            return Ok(BoxedBytes::empty());
+        }
+
+        self.expect(b"\"")?;
+
+        let start = self.index;
+        while self.peek().is_some_and(|c| c != b'"') {
+            self.index += 1;
+        }
+        let encoded = &self.json[start..self.index];
+
+        self.expect(b"\"")?;
+
+        if encoded.is_empty() || !encoded.len().is_multiple_of(4) {
+            return Err(Self::invalid());
+        }
+
+        let mut bytes = Vec::with_capacity(encoded.len() / 4 * 3);
+        let chunk_count = encoded.len() / 4;
+        for (i, chunk) in encoded.chunks(4).enumerate() {
+            let is_last = i == chunk_count - 1;
+            let padding = chunk.iter().rev().take_while(|c| **c == BASE64_PADDING).count();
+            if padding > 2 || (padding > 0 && !is_last) {
+                return Err(Self::invalid());
+            }
+
+            let mut sextets = [0u8; 4];
+            for (sextet, c) in sextets.iter_mut().zip(&chunk[..4 - padding]) {
+                *sextet = base64_value(*c).ok_or_else(Self::invalid)?;
+            }
+
+            bytes.push((sextets[0] << 2) | (sextets[1] >> 4));
+            match padding {
+                0 => {
+                    bytes.push((sextets[1] << 4) | (sextets[2] >> 2));
+                    bytes.push((sextets[2] << 6) | sextets[3]);
+                },
+                1 if sextets[2] & 0x03 == 0 => bytes.push((sextets[1] << 4) | (sextets[2] >> 2)),
+                2 if sextets[1] & 0x0f == 0 => {},
+                _ => return Err(Self::invalid())
+            }
+        }
+
+        Ok(BoxedBytes::from(bytes.as_slice()))
+    }
+
+    fn list<T, F: Fn(&mut Self) -> Result<T, DecodeError>>(&mut self, read_item: F) -> Result<Vec<T>, DecodeError> {
+        let mut items = Vec::new();
+        if self.null()? {
+            return Ok(items);
+        }
+
+        self.expect(b"[")?;
+        loop {
+            items.push(read_item(self)?);
+
+            if !self.next_is(b",") {
+                break;
+            }
+        }
+        self.expect(b"]")?;
+
+        Ok(items)
+    }
+}
+
+fn base64_value(c: u8) -> Option<u8> {
+    BASE64_ALPHABET.iter().position(|a| *a == c).map(|value| value as u8)
+}
@@ -0,0 +1,58 @@
+use elrond_wasm::{H256, derive_imports};
+
+use super::*;
+
+derive_imports!();
+
+/// What is kept in storage for each synced header. The bookkeepers and signatures are only needed once, for verification.
+/// The full header can be found in the block header sync event logs.
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, Debug, PartialEq)]
+pub struct HeaderRecord {
+	pub height: u32,
+	pub block_hash: H256,
+	pub transactions_root: H256,
+	pub cross_state_root: H256,
+	pub block_root: H256,
+	pub timestamp: u32,
+	pub is_key_header: bool, // the header changed the consensus
+}
+
+impl HeaderRecord {
+	/// _block_hash_ is passed separately, as it's computed by the contract
+	pub fn from_header(header: &Header, block_hash: H256) -> Self {
+		let is_key_header = match &header.consensus_payload {
+			Some(payload) => payload.new_chain_config.is_some(),
+			None => false
+		};
+
+		HeaderRecord {
+			height: header.height,
+			block_hash,
+			transactions_root: header.transactions_root.clone(),
+			cross_state_root: header.cross_state_root.clone(),
+			block_root: header.block_root.clone(),
+			timestamp: header.timestamp,
+			is_key_header,
+		}
+	}
+}
+
+/// The hash and roots of a header, as needed to verify cross chain transactions against it
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, Debug, PartialEq)]
+pub struct HeaderRoots {
+	pub block_hash: H256,
+	pub transactions_root: H256,
+	pub cross_state_root: H256,
+	pub block_root: H256,
+}
+
+impl HeaderRoots {
+	pub fn from_record(record: &HeaderRecord) -> Self {
+		HeaderRoots {
+			block_hash: record.block_hash.clone(),
+			transactions_root: record.transactions_root.clone(),
+			cross_state_root: record.cross_state_root.clone(),
+			block_root: record.block_root.clone(),
+		}
+	}
+}
@@ -1,21 +1,51 @@
 #![no_std]
 
-use elrond_wasm::{Address, BoxedBytes, H256, Vec, derive_imports};
+use elrond_wasm::{H256, Vec, derive_imports};
 use elrond_wasm::elrond_codec::*;
 
 use util::*;
 use vbft_block_info::*;
-use zero_copy_sink::*;
+use zero_copy_derive::*;
 use zero_copy_source::*;
 
 pub mod peer_config;
 pub mod chain_config;
 pub mod vbft_block_info;
+pub mod header_record;
+pub mod consensus_payload;
 
 derive_imports!();
 
-#[derive(TypeAbi, Debug, PartialEq)]
+pub const UNSUPPORTED_HEADER_VERSION_ERR_MSG: &[u8] = b"unsupported header version";
+
+/// The header layouts that can be decoded, by the value of the header's version field.
+/// A new layout needs a new variant and its own decode function in Header's ZeroCopyDecode implementation,
+/// so headers of older versions are still decoded the same way.
+#[derive(TypeAbi, Debug, PartialEq, Clone, Copy)]
+pub enum HeaderVersion {
+    V0, // the current Poly header
+}
+
+impl HeaderVersion {
+    pub fn from_u32(version: u32) -> Option<Self> {
+        match version {
+            0 => Some(HeaderVersion::V0),
+            _ => None
+        }
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        match self {
+            HeaderVersion::V0 => 0
+        }
+    }
+}
+
+/// The partial serialization, without the bookkeepers and signatures, is the part that gets hashed and signed.
+/// The fields are laid out as in version 0 headers. Decoding reads the version first, and dispatches on it.
+#[derive(ZeroCopyEncode, TypeAbi, Debug, PartialEq)]
 pub struct Header {
+    #[zero_copy(validate = "check_header_version")]
     pub version: u32,
     pub chain_id: u64,
     pub prev_block_hash: H256,
@@ -25,225 +55,134 @@ pub struct Header {
     pub timestamp: u32,
     pub height: u32,
     pub consensus_data: u64,
-	pub consensus_payload: Option<VbftBlockInfo>, // This is serialized as: 
-		// byte(0) if value does not exist
-		// byte(1) followed by the actual value if it exists
-	pub next_book_keeper: Address,
-
-	pub book_keepers: Vec<PublicKey>,
-	pub sig_data: Vec<Signature>,
-	pub block_hash: H256
+    #[zero_copy(with = "consensus_payload")]
+    pub consensus_payload: Option<VbftBlockInfo>, // var bytes holding its JSON, empty if there's none
+    pub next_book_keeper: PolyAddress,
+
+    #[zero_copy(skip_partial, with = "book_keepers")]
+    pub book_keepers: Vec<PublicKey>,
+    #[zero_copy(skip_partial, with = "sig_data")]
+    pub sig_data: Vec<Signature>,
+    #[zero_copy(skip_partial)]
+    pub block_hash: H256
 }
 
 impl Header {
-	pub fn is_start_of_epoch(&self) -> bool {
-		self.height % POLYCHAIN_EPOCH_HEIGHT == 0
-	}
-
-	pub fn get_partial_serialized(&self) -> BoxedBytes {
-		self.serialize_partial().get_sink()
-	}
-
-	pub fn decode_from_source(source: &mut ZeroCopySource) -> Result<Self, DecodeError> {
-		let version;
-		let chain_id;
-		let prev_block_hash;
-		let transactions_root;
-		let cross_state_root;
-		let block_root;
-		let timestamp;
-		let height;
-		let consensus_data;
-		let consensus_payload;
-		let next_book_keeper;
-		let mut book_keepers = Vec::new();
-		let mut sig_data = Vec::new();
-		let block_hash;
-
-		match source.next_u32() {
-			Some(val) => version = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_u64() {
-			Some(val) => chain_id = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_hash() {
-			Some(val) => prev_block_hash = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_hash() {
-			Some(val) => transactions_root = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_hash() {
-			Some(val) => cross_state_root = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_hash() {
-			Some(val) => block_root = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_u32() {
-			Some(val) => timestamp = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_u32() {
-			Some(val) => height = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_u64() {
-			Some(val) => consensus_data = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_u8() {
-			Some(val) => {
-				if val == 0 {
-					consensus_payload = None;
-				}
-				else if val == 1 {
-					match VbftBlockInfo::decode_from_source(source) {
-						Ok(bi) => consensus_payload = Some(bi),
-						Err(err) => return Err(err)
-					}
-				}
-				else {
-					return Err(DecodeError::INVALID_VALUE);
-				}
-			},
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		}
-
-		match source.next_address() {
-			Some(val) => next_book_keeper = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		match source.next_var_uint() {
-			Some(len) => {
-				for _ in 0..len {
-					match source.next_public_key() {
-						Some(val) => book_keepers.push(val),
-						None => return Err(DecodeError::INPUT_TOO_SHORT)
-					}
-				}
-			},
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		}
-
-		match source.next_var_uint() {
-			Some(len) => {
-				for _ in 0..len {
-					match source.next_signature() {
-						Some(val) => sig_data.push(val),
-						None => return Err(DecodeError::INPUT_TOO_SHORT)
-					}
-				}
-			},
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		}
-
-		match source.next_hash() {
-			Some(val) => block_hash = val,
-			None => return Err(DecodeError::INPUT_TOO_SHORT)
-		};
-
-		Ok(Header {
-			version,
-			chain_id,
-			prev_block_hash,
-			transactions_root,
-			cross_state_root,
-			block_root,
-			timestamp,
-			height,
-			consensus_data,
-			consensus_payload,
-			next_book_keeper,
-			book_keepers,
-			sig_data,
-			block_hash,
-		})
-	}
+    pub fn is_start_of_epoch(&self, epoch_length: u32) -> bool {
+        self.height.is_multiple_of(epoch_length)
+    }
+
+    /// None for versions this crate can't encode or decode
+    pub fn header_version(&self) -> Option<HeaderVersion> {
+        HeaderVersion::from_u32(self.version)
+    }
+}
+
+/// Only headers of known versions can be encoded
+fn check_header_version(version: &u32) -> Result<(), &'static [u8]> {
+    match HeaderVersion::from_u32(*version) {
+        Some(_) => Ok(()),
+        None => Err(UNSUPPORTED_HEADER_VERSION_ERR_MSG)
+    }
+}
+
+/// Unknown versions are rejected, as their layout can't be known.
+/// The version is the first field, so nothing else is decoded for them.
+impl ZeroCopyDecode for Header {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        let version = u32::decode_from_source(source)?;
+
+        match HeaderVersion::from_u32(version) {
+            Some(HeaderVersion::V0) => Header::decode_v0_from_source(version, source),
+            None => Err(DecodeError::from(UNSUPPORTED_HEADER_VERSION_ERR_MSG))
+        }
+    }
 }
 
-// private methods
 impl Header {
-	fn serialize_partial(&self) -> ZeroCopySink {
-		let mut sink = ZeroCopySink::new();
-
-		sink.write_u32(self.version);
-		sink.write_u64(self.chain_id);
-		sink.write_hash(&self.prev_block_hash);
-		sink.write_hash(&self.transactions_root);
-		sink.write_hash(&self.cross_state_root);
-		sink.write_hash(&self.block_root);
-		sink.write_u32(self.timestamp);
-		sink.write_u32(self.height);
-		sink.write_u64(self.consensus_data);
-
-		if let Some(payload) = &self.consensus_payload {
-			sink.write_u8(1);
-			let _ = payload.dep_encode(&mut sink);
-		}
-		else {
-			sink.write_u8(0);
-		}
-
-		sink.write_address(&self.next_book_keeper);
-
-		sink
-	}
+    fn decode_v0_from_source<S: ZeroCopyRead>(version: u32, source: &mut S) -> Result<Self, DecodeError> {
+        Ok(Header {
+            version,
+            chain_id: u64::decode_from_source(source)?,
+            prev_block_hash: H256::decode_from_source(source)?,
+            transactions_root: H256::decode_from_source(source)?,
+            cross_state_root: H256::decode_from_source(source)?,
+            block_root: H256::decode_from_source(source)?,
+            timestamp: u32::decode_from_source(source)?,
+            height: u32::decode_from_source(source)?,
+            consensus_data: u64::decode_from_source(source)?,
+            consensus_payload: consensus_payload::decode_from_source(source)?,
+            next_book_keeper: PolyAddress::decode_from_source(source)?,
+            book_keepers: book_keepers::decode_from_source(source)?,
+            sig_data: sig_data::decode_from_source(source)?,
+            block_hash: H256::decode_from_source(source)?
+        })
+    }
 }
 
-impl NestedEncode for Header {
-	fn dep_encode<O: NestedEncodeOutput>(&self, dest: &mut O) -> Result<(), EncodeError> {
-		let mut sink = self.serialize_partial();
-		
-		sink.write_var_uint(self.book_keepers.len() as u64);
-		for pubkey in &self.book_keepers {
-			sink.write_public_key(pubkey);
-		}
+/// Each bookkeeper is var bytes, as Poly writes the keys serialized by keypair.SerializePublicKey
+mod book_keepers {
+    use super::*;
+    use zero_copy_sink::*;
 
-		sink.write_var_uint(self.sig_data.len() as u64);
-		for sig in &self.sig_data {
-			sink.write_signature(sig);
-		}
+    pub fn encode_to_sink<O: NestedEncodeOutput>(keys: &[PublicKey], sink: &mut O) -> Result<(), EncodeError> {
+        sink.write_var_uint(keys.len() as u64);
+        for key in keys {
+            sink.write_var_bytes(key.to_bytes().as_slice());
+        }
 
-		sink.write_hash(&self.block_hash);
+        Ok(())
+    }
 
-		dest.write(sink.get_sink().as_slice());
+    pub fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Vec<PublicKey>, DecodeError> {
+        let len = source.next_list_len()?;
 
-		Ok(())
-	}
+        let mut keys = Vec::with_capacity(len);
+        for _ in 0..len {
+            keys.push(PublicKey::try_from_slice(source.read_var_bytes()?)?);
+        }
+
+        Ok(keys)
+    }
 }
 
-impl NestedDecode for Header {
-	fn dep_decode<I: NestedDecodeInput>(input: &mut I) -> Result<Self, DecodeError> {
-		let mut source = ZeroCopySource::new(input.flush());
+/// Each signature is var bytes, so signatures of the wrong length are rejected instead of misaligning the rest
+mod sig_data {
+    use super::*;
+    use core::convert::TryFrom;
+    use zero_copy_sink::*;
+
+    pub fn encode_to_sink<O: NestedEncodeOutput>(sigs: &[Signature], sink: &mut O) -> Result<(), EncodeError> {
+        sink.write_var_uint(sigs.len() as u64);
+        for sig in sigs {
+            sink.write_var_bytes(sig.as_slice());
+        }
+
+        Ok(())
+    }
 
-		Self::decode_from_source(&mut source)
-	}
+    pub fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Vec<Signature>, DecodeError> {
+        let len = source.next_list_len()?;
+
+        let mut sigs = Vec::with_capacity(len);
+        for _ in 0..len {
+            sigs.push(Signature::try_from(source.read_var_bytes()?)?);
+        }
+
+        Ok(sigs)
+    }
 }
 
-impl TopEncode for Header {
-	#[inline]
-	fn top_encode<O: TopEncodeOutput>(&self, output: O) -> Result<(), EncodeError> {
-		top_encode_from_nested(self, output)
-	}
+impl NestedDecode for Header {
+    fn dep_decode<I: NestedDecodeInput>(input: &mut I) -> Result<Self, DecodeError> {
+        let mut source = ZeroCopyInput::new(input);
+
+        Header::decode_from_source(&mut source)
+    }
 }
 
 impl TopDecode for Header {
-	fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
-		top_decode_from_nested(input)
-	}
+    fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
+        top_decode_from_source(input)
+    }
 }
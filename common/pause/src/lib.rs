@@ -0,0 +1,48 @@
+#![no_std]
+
+use elrond_wasm::{imports, only_owner, require, sc_error};
+
+imports!();
+
+/// The guardian, shared by the BlockHeaderSync, CrossChainManagement and EsdtTokenManager contracts.
+/// The guardian may pause, but only the owner may unpause.
+/// What can be paused is up to the contract, which checks the caller through require_guardian_or_owner.
+#[elrond_wasm_derive::module(PauseModuleImpl)]
+pub trait PauseModule {
+    // endpoints - owner-only
+
+    #[endpoint(setGuardianAddress)]
+    fn set_guardian_address_endpoint(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_guardian_address(&address);
+
+        Ok(())
+    }
+
+    // private
+
+    fn require_guardian_or_owner(&self) -> SCResult<()> {
+        let caller = self.get_caller();
+
+        require!(
+            caller == self.get_owner_address()
+                || (!self.is_empty_guardian_address() && caller == self.get_guardian_address()),
+            "only guardian or owner may call this function"
+        );
+
+        Ok(())
+    }
+
+    // storage
+
+    #[view(getGuardianAddress)]
+    #[storage_get("guardianAddress")]
+    fn get_guardian_address(&self) -> Address;
+
+    #[storage_set("guardianAddress")]
+    fn set_guardian_address(&self, address: &Address);
+
+    #[storage_is_empty("guardianAddress")]
+    fn is_empty_guardian_address(&self) -> bool;
+}
@@ -0,0 +1,115 @@
+#![no_std]
+
+use elrond_wasm::{imports, only_owner, require, sc_error};
+
+imports!();
+
+/// Relayer rewards, shared by the BlockHeaderSync and CrossChainManagement contracts.
+/// Rewards are paid from one pool per token, funded only with bridge fees.
+/// Which relayers are credited, and for what, is up to the contract.
+#[elrond_wasm_derive::module(RelayerRewardsModuleImpl)]
+pub trait RelayerRewardsModule {
+    // endpoints - owner-only
+
+    /// The contract collecting the bridge fees, which forwards part of them through depositRewardFunds
+    #[endpoint(setRewardFundsSource)]
+    fn set_reward_funds_source_endpoint(&self, address: Address) -> SCResult<()> {
+        only_owner!(self, "only owner may call this function");
+
+        self.set_reward_funds_source(&address);
+
+        Ok(())
+    }
+
+    // endpoints
+
+    #[payable("*")]
+    #[endpoint(depositRewardFunds)]
+    fn deposit_reward_funds(
+        &self,
+        #[payment_token] token_identifier: TokenIdentifier,
+        #[payment] payment: BigUint,
+    ) -> SCResult<()> {
+        require!(
+            !self.is_empty_reward_funds_source() && self.get_caller() == self.get_reward_funds_source(),
+            "Only the reward funds source may deposit reward funds"
+        );
+        require!(payment > 0, "Payment must be more than 0");
+
+        self.add_reward_funds(&token_identifier, &payment);
+
+        Ok(())
+    }
+
+    #[endpoint(claimRewards)]
+    fn claim_rewards(&self, token_identifier: TokenIdentifier) -> SCResult<()> {
+        let relayer = self.get_caller();
+        let rewards = self.get_accrued_rewards(&relayer, &token_identifier);
+
+        require!(rewards > 0, "No rewards to claim");
+
+        self.clear_accrued_rewards(&relayer, &token_identifier);
+
+        self.send().direct(&relayer, &token_identifier, &rewards, b"relayer rewards");
+
+        Ok(())
+    }
+
+    // private
+
+    fn add_reward_funds(&self, token_identifier: &TokenIdentifier, amount: &BigUint) {
+        let mut reward_pool_balance = self.get_reward_pool_balance(token_identifier);
+        reward_pool_balance += amount;
+        self.set_reward_pool_balance(token_identifier, &reward_pool_balance);
+    }
+
+    // if the reward pool runs low, the relayer is credited whatever is left in it
+    fn credit_relayer_reward(&self, relayer: &Address, token_identifier: &TokenIdentifier, reward: &BigUint) {
+        let mut reward_pool_balance = self.get_reward_pool_balance(token_identifier);
+        let credited = if reward < &reward_pool_balance {
+            reward.clone()
+        } else {
+            reward_pool_balance.clone()
+        };
+
+        if credited == 0 {
+            return;
+        }
+
+        reward_pool_balance -= &credited;
+        self.set_reward_pool_balance(token_identifier, &reward_pool_balance);
+
+        let mut accrued_rewards = self.get_accrued_rewards(relayer, token_identifier);
+        accrued_rewards += credited;
+        self.set_accrued_rewards(relayer, token_identifier, &accrued_rewards);
+    }
+
+    // storage
+
+    #[view(getRewardFundsSource)]
+    #[storage_get("rewardFundsSource")]
+    fn get_reward_funds_source(&self) -> Address;
+
+    #[storage_set("rewardFundsSource")]
+    fn set_reward_funds_source(&self, address: &Address);
+
+    #[storage_is_empty("rewardFundsSource")]
+    fn is_empty_reward_funds_source(&self) -> bool;
+
+    #[view(getRewardPoolBalance)]
+    #[storage_get("rewardPoolBalance")]
+    fn get_reward_pool_balance(&self, token_identifier: &TokenIdentifier) -> BigUint;
+
+    #[storage_set("rewardPoolBalance")]
+    fn set_reward_pool_balance(&self, token_identifier: &TokenIdentifier, balance: &BigUint);
+
+    #[view(getAccruedRewards)]
+    #[storage_get("accruedRewards")]
+    fn get_accrued_rewards(&self, relayer: &Address, token_identifier: &TokenIdentifier) -> BigUint;
+
+    #[storage_set("accruedRewards")]
+    fn set_accrued_rewards(&self, relayer: &Address, token_identifier: &TokenIdentifier, rewards: &BigUint);
+
+    #[storage_clear("accruedRewards")]
+    fn clear_accrued_rewards(&self, relayer: &Address, token_identifier: &TokenIdentifier);
+}
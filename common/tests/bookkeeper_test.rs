@@ -0,0 +1,69 @@
+extern crate util;
+use util::bookkeeper::*;
+use util::ripemd160::*;
+use util::PublicKey;
+
+use sha2::{Digest, Sha256};
+
+// uncompressed secp256k1 key, with X filled with _x_ and the given last byte of Y
+fn test_key(x: u8, y_last_byte: u8) -> PublicKey {
+    let mut key = [x; 67];
+    key[..3].copy_from_slice(&[0x12, 0x05, 0x04]);
+    key[66] = y_last_byte;
+
+    PublicKey::try_from_slice(&key[..]).unwrap()
+}
+
+#[test]
+fn bookkeeper_program_test() {
+    let single = bookkeeper_program(&[test_key(0x11, 0x10)]);
+    assert_eq!(single.len(), 37);
+    assert_eq!(single[0], 35);
+    assert_eq!(&single[1..4], &[0x12, 0x05, 0x02]);
+    assert_eq!(single[36], 0xac);
+
+    // keys are sorted, so the order they're given in does not matter
+    let program = bookkeeper_program(&[test_key(0x22, 0x23), test_key(0x11, 0x10)]);
+    assert_eq!(program, bookkeeper_program(&[test_key(0x11, 0x10), test_key(0x22, 0x23)]));
+    assert_eq!(&program[..2], &[2, 0]);
+    assert_eq!(program[2], 35);
+    assert_eq!(&program[3..6], &[0x12, 0x05, 0x02]); // even Y
+    assert_eq!(&program[6..38], &[0x11u8; 32][..]);
+    assert_eq!(program[38], 35);
+    assert_eq!(&program[39..42], &[0x12, 0x05, 0x03]); // odd Y
+    assert_eq!(&program[74..], &[2, 0]);
+
+    assert_eq!(bookkeeper_threshold(4), 3);
+    assert_eq!(bookkeeper_threshold(7), 5);
+}
+
+// secp256k1 keys in Poly's uncompressed form, valid curve points
+const CONSENSUS_KEYS: [&str; 7] = [
+    "120504bd638997161ab9df3ece8aacb15f0ae3375ce3927b8ac538aa86f19d4842d987d32e4eb97a01bdc24d55e4505bded27160f5e0fa2feada911c7da70af1430e5b",
+    "12050488e0167942e5d864411a94c81e92614f95048bed27e7b6b14cd5ff661483f9177053c7b7e073ad170c1d6a649b28b17691688f8c1633d95d21c7a4fb5fcd1530",
+    "120504f36f709089ff9b1c399afa20d5aab3c420fd2552d9004db557e360b14fe434d05faf876c32cc958d3b2157c755afa918cb886335d3c80640c236deec7eed44c0",
+    "120504ea7e74180e2f07d1fd15da33fa2b9aa0d2111f4b7f5f098dd5031d495e8af84257902b78cc3a1ff13837d4f527e6d597de3f01631fed145967efc3f9752d5007",
+    "120504dcf76c9932535cb923f363aa8faf76cb306c0f77f697a9e5908abb6f87a7edb77924b2f1c858f4ad58f5484f3ee146be023ad73ab9d55a1e9a168e28613425aa",
+    "12050482e5f2a16aaa6c89e0040bb079cb49b51f535232e506c2df89a14cc8ed1646508bf5b9afbe27b6d33c030843954783c34249cec9566a908bbe660f43dbf93a6f",
+    "1205045a33a413f5242bb4de90aff00693457793c36411d3563b1d402c76700618746d9407538f460323f236c77067a832710c842e9e6a4778062ae06801d0ae159c07",
+];
+
+fn bookkeeper_address(keys: &[PublicKey]) -> String {
+    let program_hash = Sha256::digest(bookkeeper_program(keys).as_slice());
+
+    hex::encode(ripemd160(&program_hash[..]))
+}
+
+// the addresses were computed by porting Poly's AddressFromBookkeepers (core/types/address.go), independently of this crate
+#[test]
+fn bookkeeper_address_test() {
+    let decode = |key: &&str| PublicKey::try_from_slice(&hex::decode(key).unwrap()).unwrap();
+    let keys: Vec<PublicKey> = CONSENSUS_KEYS.iter().map(decode).collect();
+
+    assert_eq!(bookkeeper_address(&keys[..1]), "f60c1063e961c26932e9c00ebfe83d472d8c224a");
+    assert_eq!(bookkeeper_address(&keys[..4]), "a64dfd9dc6f9cb018db55ea9611d4b13e70e22f0");
+    assert_eq!(bookkeeper_address(&keys), "3ccd5652d796bf9c97a39ca393197c62ec20b561");
+
+    let reversed: Vec<PublicKey> = CONSENSUS_KEYS.iter().rev().map(decode).collect();
+    assert_eq!(bookkeeper_address(&reversed), "3ccd5652d796bf9c97a39ca393197c62ec20b561");
+}
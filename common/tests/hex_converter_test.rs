@@ -0,0 +1,25 @@
+extern crate util;
+use util::hex_converter::*;
+
+use elrond_wasm::BoxedBytes;
+
+#[test]
+fn hex_converter_round_trip_test() {
+    let original = [0x12u8, 0x05u8, 0xabu8, 0xcdu8, 0xefu8];
+
+    let hex = byte_slice_to_hex(&original[..]);
+    assert_eq!(hex.as_slice(), b"1205abcdef");
+
+    match hex_to_byte_slice(hex.as_slice()) {
+        Some(bytes) => assert_eq!(bytes, BoxedBytes::from(&original[..])),
+        None => panic!("Valid hex was rejected")
+    };
+
+    assert_eq!(hex_to_byte_slice(b"1205ABCDEF"), Some(BoxedBytes::from(&original[..])));
+}
+
+#[test]
+fn hex_to_byte_slice_invalid_test() {
+    assert_eq!(hex_to_byte_slice(b"120"), None);
+    assert_eq!(hex_to_byte_slice(b"12zz"), None);
+}
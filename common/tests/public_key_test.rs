@@ -0,0 +1,81 @@
+extern crate util;
+use util::*;
+
+use elrond_wasm::elrond_codec::*;
+
+#[test]
+fn uncompressed_key_test() {
+    let mut key_bytes = [0x33u8; POLYCHAIN_PUBKEY_LEN];
+    key_bytes[..3].copy_from_slice(&[0x12, 0x05, 0x04]);
+    key_bytes[66] = 0x01;
+
+    let key = PublicKey::try_from_slice(&key_bytes[..]).unwrap();
+
+    assert_eq!(key.algorithm(), KeyAlgorithm::Ecdsa);
+    assert_eq!(key.curve(), Curve::Secp256k1);
+    assert_eq!(key.x_coordinate(), &[0x33u8; 32][..]);
+    assert_eq!(key.to_bytes().as_slice(), &key_bytes[..]);
+
+    let compressed = key.to_compressed_bytes();
+    assert_eq!(compressed.len(), 35);
+    assert_eq!(&compressed.as_slice()[..3], &[0x12, 0x05, 0x03]);
+}
+
+#[test]
+fn compressed_and_ed25519_keys_test() {
+    let mut compressed = [0x44u8; 35];
+    compressed[..3].copy_from_slice(&[0x13, 20, 0x02]);
+
+    let key = PublicKey::try_from_slice(&compressed[..]).unwrap();
+    assert_eq!(key.algorithm(), KeyAlgorithm::Sm2);
+    assert_eq!(key.curve(), Curve::Sm2P256V1);
+    assert_eq!(key.to_compressed_bytes().as_slice(), &compressed[..]);
+
+    let mut ed25519 = [0x55u8; 34];
+    ed25519[..2].copy_from_slice(&[0x14, 25]);
+
+    let key = PublicKey::try_from_slice(&ed25519[..]).unwrap();
+    assert_eq!(key.algorithm(), KeyAlgorithm::EdDsa);
+    assert_eq!(key.point(), &[0x55u8; 32][..]);
+}
+
+#[test]
+fn nested_keys_test() {
+    let mut ed25519 = [0x55u8; 34];
+    ed25519[..2].copy_from_slice(&[0x14, 25]);
+    let mut compressed = [0x44u8; 35];
+    compressed[..3].copy_from_slice(&[0x12, 0x02, 0x03]);
+
+    // keys of different lengths, one after the other
+    let mut serialized = Vec::new();
+    serialized.extend_from_slice(&ed25519[..]);
+    serialized.extend_from_slice(&compressed[..]);
+
+    let input = &mut serialized.as_slice();
+    assert_eq!(PublicKey::dep_decode(input).unwrap().curve(), Curve::Ed25519);
+    assert_eq!(PublicKey::dep_decode(input).unwrap().curve(), Curve::P256);
+    assert!(input.is_empty());
+}
+
+#[test]
+fn malformed_keys_test() {
+    let mut key_bytes = [0x33u8; POLYCHAIN_PUBKEY_LEN];
+    key_bytes[..3].copy_from_slice(&[0x12, 0x05, 0x04]);
+
+    assert_eq!(PublicKey::try_from_slice(&key_bytes[..66]), Err(DecodeError::INPUT_TOO_SHORT));
+    assert_eq!(PublicKey::try_from_slice(&[&key_bytes[..], &[0u8][..]].concat()), Err(DecodeError::INPUT_TOO_LONG));
+
+    // unknown algorithm
+    key_bytes[0] = 0x15;
+    assert_eq!(PublicKey::try_from_slice(&key_bytes[..]), Err(DecodeError::INVALID_VALUE));
+
+    // SM2 curve for an ECDSA key
+    key_bytes[0] = 0x12;
+    key_bytes[1] = 20;
+    assert_eq!(PublicKey::try_from_slice(&key_bytes[..]), Err(DecodeError::INVALID_VALUE));
+
+    // invalid point prefix
+    key_bytes[1] = 0x05;
+    key_bytes[2] = 0x05;
+    assert_eq!(PublicKey::try_from_slice(&key_bytes[..]), Err(DecodeError::INVALID_VALUE));
+}
@@ -0,0 +1,22 @@
+extern crate util;
+use util::ripemd160::*;
+
+#[test]
+fn ripemd160_test() {
+    assert_eq!(
+        hex::encode(ripemd160(b"")),
+        "9c1185a5c5e9fc54612808977ee8f548b2258d31"
+    );
+    assert_eq!(
+        hex::encode(ripemd160(b"abc")),
+        "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"
+    );
+    assert_eq!(
+        hex::encode(ripemd160(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+        "12a053384a9c0c88e405a06c27dcf49ada62eb2b"
+    );
+    assert_eq!(
+        hex::encode(ripemd160(&[b'a'; 1000][..])),
+        "aa69deee9a8922e92f8105e007f76110f381e9cf"
+    );
+}
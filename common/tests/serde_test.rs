@@ -5,7 +5,13 @@ use peer_config::*;
 use chain_config::*;
 use vbft_block_info::*;
 
-use elrond_wasm::{Address, BoxedBytes, H256, Vec, elrond_codec::*};
+use std::convert::TryFrom;
+
+use elrond_wasm::{BoxedBytes, H256, Vec, elrond_codec::*};
+
+use util::*;
+
+use sha2::{Digest, Sha256};
 
 #[test]
 fn peer_config_serde_test() {
@@ -29,6 +35,39 @@ fn peer_config_serde_test() {
     assert_eq!(deserialized, original);
 }
 
+#[test]
+fn public_key_serde_test() {
+    let mut key_bytes = [0u8; POLYCHAIN_PUBKEY_LEN];
+    key_bytes[0] = 0x12;
+    key_bytes[1] = 0x05;
+    key_bytes[2] = 0x04;
+    key_bytes[66] = 0xff;
+
+    let original = match PublicKey::try_from_slice(&key_bytes[..]) {
+        Ok(key) => key,
+        Err(err) => panic!("Valid key was rejected: {:?}",
+            String::from_utf8(err.message_bytes().to_vec()))
+    };
+
+    let mut serialized = Vec::new();
+    let _ = original.dep_encode(&mut serialized);
+
+    assert_eq!(serialized, key_bytes.to_vec());
+
+    let deserialized = match PublicKey::dep_decode(&mut serialized.as_slice()) {
+        Ok(des) => des,
+        Err(err) => panic!("Deserialization error: {:?}", 
+            String::from_utf8(err.message_bytes().to_vec()))
+    };
+
+    assert_eq!(deserialized, original);
+
+    match PublicKey::dep_decode(&mut &key_bytes[..POLYCHAIN_PUBKEY_LEN - 1]) {
+        Ok(_) => panic!("Short input should not decode"),
+        Err(err) => assert_eq!(err, DecodeError::INPUT_TOO_SHORT)
+    };
+}
+
 #[test]
 fn chain_config_serde_test() {
     let mut peers = Vec::new();
@@ -201,7 +240,7 @@ fn header_test() {
     });
 
     let mut original = Header {
-        version: 5,
+        version: 0,
         chain_id: 6,
         prev_block_hash: H256::from([0x42, 0xa1, 0xff, 0xcd, 0xd6, 0xfd, 0x49, 0xa1, 0xdb, 0xb7, 0xdf, 0x16, 0x36, 0xe6, 0x25, 0xfd, 0xc2, 0xfb, 0x52, 0xc1
             , 0x0c, 0x6e, 0x2b, 0x55, 0xd0, 0x8c, 0xc4, 0x6c, 0xd7, 0x09, 0x70, 0x92]),
@@ -232,7 +271,7 @@ fn header_test() {
                 max_block_change_view: 16
             })}
         ),
-        next_book_keeper: Address::zero(),
+        next_book_keeper: PolyAddress::from([0x5bu8; 20]),
         book_keepers: Vec::new(),
         sig_data: Vec::new(),
         block_hash: H256::from([0x26, 0x46, 0x0e, 0xd3, 0x76, 0x17, 0x95, 0x7c, 0x96, 0xd9, 0xab, 0xf5, 0x94, 0xa1, 0xac, 0x86, 0x5a, 0x43, 0x11, 0x02
@@ -264,4 +303,216 @@ fn header_test() {
     };
 
     assert_eq!(original, deserialized);
+
+    // unknown versions can be neither encoded nor decoded
+    original.version = 1;
+    serialized.clear();
+    assert_eq!(original.dep_encode(&mut serialized),
+        Err(EncodeError::from(UNSUPPORTED_HEADER_VERSION_ERR_MSG)));
+
+    original.version = 0;
+    let _ = original.dep_encode(&mut serialized);
+    serialized[0] = 1;
+    match Header::dep_decode(&mut serialized.as_slice()) {
+        Ok(_) => panic!("Unknown header version should not decode"),
+        Err(err) => assert_eq!(err, DecodeError::from(UNSUPPORTED_HEADER_VERSION_ERR_MSG))
+    };
+
+    // unsigned part, as hashed by Poly: fixed-size fields, the empty payload's length, then the 20-byte next bookkeeper
+    let partial = original.get_partial_serialized().unwrap();
+    assert_eq!(partial.len(), 4 + 8 + 4 * 32 + 4 + 4 + 8 + 1 + 20);
+    assert_eq!(&partial.as_slice()[partial.len() - 20..], &[0x5bu8; 20][..]);
+}
+
+/// A header laid out as Poly's Go node serializes it: the unsigned part (an empty consensus payload is written as empty var bytes),
+/// then the bookkeeper and signature counts. The block hash is appended at the end, as the relayers send it.
+const POLY_HEADER: [u8; 211] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xfa, 0x38, 0xe7, 0x31,
+    0x6c, 0x31, 0x03, 0xb8, 0x29, 0x93, 0xd0, 0xd7, 0xf4, 0x09, 0xec, 0xb6, 0x5f, 0xad, 0x7c, 0x56,
+    0x5b, 0xd1, 0x13, 0x6f, 0x41, 0x4e, 0x9c, 0xbf, 0x73, 0xfc, 0x01, 0x5e, 0x7a, 0x5c, 0x1f, 0xb6,
+    0xf3, 0x3e, 0xb2, 0xed, 0x9f, 0xf9, 0x8d, 0xf1, 0x2e, 0x8d, 0xa9, 0x43, 0x9d, 0x94, 0xf3, 0x25,
+    0xcd, 0x86, 0x3b, 0xe5, 0x93, 0x8c, 0xff, 0x88, 0xa4, 0x02, 0x76, 0x51, 0x4d, 0x2c, 0x8e, 0xd4,
+    0x8b, 0xec, 0x22, 0x0c, 0x86, 0x3c, 0x09, 0xb0, 0xc9, 0x8d, 0x6d, 0x79, 0x0e, 0x0a, 0x72, 0x28,
+    0x34, 0x5c, 0x09, 0x50, 0x04, 0x6d, 0xb8, 0x7d, 0x63, 0xb5, 0x4b, 0xcd, 0x3a, 0x0c, 0x73, 0x73,
+    0xb2, 0x28, 0x8d, 0x80, 0x6d, 0xcd, 0x5c, 0xbc, 0x06, 0xae, 0x82, 0x1c, 0xdc, 0x87, 0x8e, 0x0a,
+    0x91, 0x4f, 0xe5, 0x46, 0x1e, 0x91, 0xe0, 0x44, 0xb9, 0xbb, 0xa9, 0xc0, 0x00, 0x10, 0x5e, 0x5f,
+    0x00, 0x10, 0x00, 0x00, 0x5e, 0x1b, 0x2c, 0x6f, 0x2a, 0xd0, 0x3b, 0x1d, 0x00, 0xa4, 0x90, 0x70,
+    0x6f, 0xe0, 0xd7, 0xee, 0x87, 0x8a, 0x7b, 0x35, 0x91, 0x86, 0x17, 0x20, 0x47, 0xbe, 0xfd, 0xb1,
+    0xe4, 0x00, 0x00, 0x5b, 0xfa, 0x3b, 0x2a, 0xc9, 0xe8, 0xaa, 0xa2, 0x9b, 0x28, 0xca, 0x67, 0xe0,
+    0x4f, 0xa2, 0xb6, 0x1c, 0xd2, 0xd5, 0x2b, 0xe4, 0x30, 0xa2, 0x66, 0x4f, 0x66, 0xf0, 0x0f, 0x2e,
+    0x59, 0x71, 0x79,
+];
+
+#[test]
+fn poly_header_hash_test() {
+    let serialized = POLY_HEADER;
+    const UNSIGNED_LEN: usize = 177;
+
+    let header = match Header::top_decode(&serialized[..]) {
+        Ok(des) => des,
+        Err(err) => panic!("Deserialization error: {:?}", 
+            String::from_utf8(err.message_bytes().to_vec()))
+    };
+
+    assert_eq!(header.version, 0);
+    assert_eq!(header.chain_id, 0);
+    assert_eq!(header.timestamp, 1_600_000_000);
+    assert_eq!(header.height, 4096);
+    assert_eq!(header.consensus_data, 0x1d3b_d02a_6f2c_1b5e);
+    assert_eq!(header.consensus_payload, None);
+    assert!(header.book_keepers.is_empty());
+    assert!(header.sig_data.is_empty());
+
+    // the partial serialization is exactly the unsigned part Poly hashes, and its double sha256 is the block hash
+    let partial = header.get_partial_serialized().unwrap();
+    assert_eq!(partial.as_slice(), &serialized[..UNSIGNED_LEN]);
+
+    let recomputed_hash = Sha256::digest(&Sha256::digest(partial.as_slice()));
+    assert_eq!(&recomputed_hash[..], header.block_hash.as_bytes());
+    assert_eq!(&serialized[serialized.len() - 32..], header.block_hash.as_bytes());
+}
+
+/// A key header laid out as Poly's Go node serializes it, with its consensus payload as the JSON json.Marshal writes,
+/// and the bookkeepers and signatures as var bytes. The hash was computed independently, from the same fields.
+#[test]
+fn poly_key_header_hash_test() {
+    const PAYLOAD: &[u8] = br#"{"leader":2,"vrf_value":"BEw6EiqNNL7zK0jE3fpAVOwaX+Dhmkmp9CKRevXosLMvF0HAvx8KjEnh9ZTsiled8YNbljO2MRkn3cbKnWFLoVE=","vrf_proof":"0uq3GdF9Ql3HZMCb5DkvJrJ+fzunnkL0ZNWkgtzRL9PS6rcZ0X1CXcdkwJvkOS8msn5/O6eeQvRk1aSC3NEv0w==","last_config_block_num":120,"new_chain_config":{"version":1,"view":1,"n":4,"c":3,"block_msg_delay":10000,"hash_msg_delay":10000,"peer_handshake_timeout":10000,"peers":[{"index":1,"id":"1419fa82435fee9a40439f76bfb679e9c43328a67914fb293e822ed64350e7eb174e"},{"index":2,"id":"1419790b27c8833ea0958470ed8bf1b0c12b5c5ca6ce1b8cc22a24b441923141ef84"},{"index":3,"id":"1419ad2f3f7a004799d191cc8be2d700f0b88ccf2d0f086893b1bc445f3e492554c2"},{"index":4,"id":"141934dc64717dd110395dc2ce548531aa1b1374fc0d64c90581b39313605128128d"}],"pos_table":[1,2,3,4],"MaxBlockChangeView":5}}"#;
+    const FIXED_FIELDS: &str = "00000000020000000000000084fd9bac333ad79154348296204fa7f8c537a96e08983e5f73b3f5aca8e8edf71b5b9ccb3e8d006a5230de9bda23ff91edc794d4f56410560830b418528e446cb3986952b145da5f0e4bd416f3b948e9864b57895675886c1deb83c16a9beadc496aca80e4d8f29fb8e8cd816c3afb48d3f103970b3a2ee1600c08ca67326dee00105e5f780000005e1b2c6f2ad03b1d";
+    const NEXT_BOOK_KEEPER: &str = "67563873b899d291ba90a9fdd2fab7c51ad1d583";
+    const BOOK_KEEPERS: &str = "03221419fa82435fee9a40439f76bfb679e9c43328a67914fb293e822ed64350e7eb174e221419790b27c8833ea0958470ed8bf1b0c12b5c5ca6ce1b8cc22a24b441923141ef84221419ad2f3f7a004799d191cc8be2d700f0b88ccf2d0f086893b1bc445f3e492554c2";
+    const SIG_DATA: &str = "03410a80d4316c786df379f9e27d483ec5271c2d2474d04ff7a2f68e13b1587309e15cd924469d2c5dfdff323a9d63e007a1764f0023d514da2fffc59873d938d6ac0f410a20d741a5a01db714a37a2c8a87e324bc482dd043e4889c558a0817690381be2990e9b16f2adf969778585df3aed397f9aae74d006ef79618b3efc082aa2d170b410af51265f396cfeb5e1c26b633d19a4c002129955b3e9a2407c3e59cd1063dfc024e50c97ba25c47db85ca905ca4ee1222688b66d913328474f621a3e02a834e08";
+    const BLOCK_HASH: &str = "d136ca1a946d3f0eb410e69705277f9aae3569f733c00952ee3786e28ab5c772";
+
+    let mut unsigned = hex::decode(FIXED_FIELDS).unwrap();
+    unsigned.extend_from_slice(&[0xfd, 0x11, 0x03]); // the payload's length, 785
+    unsigned.extend_from_slice(PAYLOAD);
+    unsigned.extend_from_slice(&hex::decode(NEXT_BOOK_KEEPER).unwrap());
+
+    let mut serialized = unsigned.clone();
+    serialized.extend_from_slice(&hex::decode(BOOK_KEEPERS).unwrap());
+    serialized.extend_from_slice(&hex::decode(SIG_DATA).unwrap());
+    serialized.extend_from_slice(&hex::decode(BLOCK_HASH).unwrap());
+
+    let header = match Header::top_decode(serialized.as_slice()) {
+        Ok(des) => des,
+        Err(err) => panic!("Deserialization error: {:?}", 
+            String::from_utf8(err.message_bytes().to_vec()))
+    };
+
+    assert_eq!(header.chain_id, 2);
+    assert_eq!(header.height, 120);
+    assert_eq!(header.book_keepers.len(), 3);
+    assert_eq!(header.book_keepers[0].algorithm(), KeyAlgorithm::EdDsa);
+    assert_eq!(header.sig_data.len(), 3);
+
+    let payload = header.consensus_payload.as_ref().unwrap();
+    assert_eq!(payload.proposer, 2);
+    assert_eq!(payload.vrf_value.len(), 65);
+    assert_eq!(payload.vrf_proof.len(), 64);
+    assert_eq!(payload.last_config_block_num, 120);
+
+    let chain_config = payload.new_chain_config.as_ref().unwrap();
+    assert_eq!(chain_config.network_size, 4);
+    assert_eq!(chain_config.consensus_quorum, 3);
+    assert_eq!(chain_config.block_msg_delay, 10000);
+    assert_eq!(chain_config.peers.len(), 4);
+    assert_eq!(chain_config.peers[3].index, 4);
+    assert_eq!(chain_config.peers[3].id.as_slice(), &b"141934dc64717dd110395dc2ce548531aa1b1374fc0d64c90581b39313605128128d"[..]);
+    assert_eq!(chain_config.pos_table, [1u32, 2, 3, 4].to_vec());
+    assert_eq!(chain_config.max_block_change_view, 5);
+
+    // the payload encodes back to the JSON that was signed, so the header hashes to its block hash
+    assert_eq!(consensus_payload::to_json(payload).unwrap().as_slice(), PAYLOAD);
+
+    let partial = header.get_partial_serialized().unwrap();
+    assert_eq!(partial.as_slice(), unsigned.as_slice());
+
+    let recomputed_hash = Sha256::digest(&Sha256::digest(partial.as_slice()));
+    assert_eq!(hex::encode(&recomputed_hash[..]), BLOCK_HASH);
+    assert_eq!(header.block_hash.as_bytes(), &hex::decode(BLOCK_HASH).unwrap()[..]);
+
+    let mut reencoded = Vec::new();
+    let _ = header.dep_encode(&mut reencoded);
+    assert_eq!(reencoded, serialized);
+}
+
+/// Only the JSON Go writes is accepted, as any other encoding of the payload would hash differently
+#[test]
+fn consensus_payload_json_test() {
+    let canonical = br#"{"leader":1,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null}"#;
+    let block_info = match consensus_payload::from_json(canonical) {
+        Ok(block_info) => block_info,
+        Err(err) => panic!("Deserialization error: {:?}", 
+            String::from_utf8(err.message_bytes().to_vec()))
+    };
+
+    assert_eq!(block_info.vrf_value.as_slice(), &[1u8, 2, 3][..]);
+    assert_eq!(block_info.vrf_proof.as_slice(), &[1u8][..]);
+    assert_eq!(consensus_payload::to_json(&block_info).unwrap().as_slice(), &canonical[..]);
+
+    // empty byte strings are written as null, like Go's nil slices
+    let empty_vrf = br#"{"leader":1,"vrf_value":null,"vrf_proof":null,"last_config_block_num":7,"new_chain_config":null}"#;
+    let block_info = consensus_payload::from_json(empty_vrf).unwrap();
+    assert!(block_info.vrf_value.is_empty());
+    assert_eq!(consensus_payload::to_json(&block_info).unwrap().as_slice(), &empty_vrf[..]);
+
+    let rejected: [&[u8]; 10] = [
+        br#"{"leader": 1,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"vrf_value":"AQID","leader":1,"vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"leader":01,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"leader":4294967296,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"leader":1,"vrf_value":"","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"leader":1,"vrf_value":"AQID","vrf_proof":"AQ","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"leader":1,"vrf_value":"AQID","vrf_proof":"AR==","last_config_block_num":7,"new_chain_config":null}"#,
+        br#"{"leader":1,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7}"#,
+        br#"{"leader":1,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null} "#,
+        br#"{"leader":1,"vrf_value":"AQID","vrf_proof":"AQ==","last_config_block_num":7,"new_chain_config":null,"extra":0}"#,
+    ];
+    for json in rejected.iter() {
+        match consensus_payload::from_json(json) {
+            Ok(_) => panic!("Non-canonical payload should not decode: {:?}", String::from_utf8(json.to_vec())),
+            Err(err) => assert_eq!(err, DecodeError::from(consensus_payload::INVALID_CONSENSUS_PAYLOAD_ERR_MSG))
+        };
+    }
+}
+
+#[test]
+fn header_signers_layout_test() {
+    let mut header = Header::top_decode(&POLY_HEADER[..]).unwrap();
+    header.sig_data.push(Signature::try_from(&[7u8; POLYCHAIN_SIGNATURE_LEN][..]).unwrap());
+
+    let mut serialized = Vec::new();
+    let _ = header.dep_encode(&mut serialized);
+
+    // one signature, as var bytes, before the block hash
+    let sig_start = serialized.len() - 32 - POLYCHAIN_SIGNATURE_LEN - 2;
+    assert_eq!(&serialized[sig_start..sig_start + 2], &[1u8, POLYCHAIN_SIGNATURE_LEN as u8][..]);
+
+    // a signature of the wrong length is rejected, instead of shifting the block hash
+    serialized[sig_start + 1] = POLYCHAIN_SIGNATURE_LEN as u8 - 1;
+    assert!(Header::top_decode(serialized.as_slice()).is_err());
+}
+
+#[test]
+fn trailing_bytes_test() {
+    let original = PeerConfig {
+        index: 3,
+        id: BoxedBytes::from(&b"id3"[..])
+    };
+
+    let mut serialized = Vec::new();
+    let _ = original.dep_encode(&mut serialized);
+
+    match PeerConfig::top_decode(serialized.as_slice()) {
+        Ok(des) => assert_eq!(des, original),
+        Err(err) => panic!("Deserialization error: {:?}", 
+            String::from_utf8(err.message_bytes().to_vec()))
+    };
+
+    // anything after the value is rejected, instead of being silently ignored
+    serialized.push(0);
+    match PeerConfig::top_decode(serialized.as_slice()) {
+        Ok(_) => panic!("Trailing bytes should not decode"),
+        Err(err) => assert_eq!(err, DecodeError::INPUT_TOO_LONG)
+    };
 }
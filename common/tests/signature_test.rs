@@ -0,0 +1,23 @@
+extern crate util;
+use util::*;
+
+use core::convert::TryFrom;
+
+use elrond_wasm::elrond_codec::*;
+
+#[test]
+fn signature_length_test() {
+    let mut sig_bytes = [0x33u8; POLYCHAIN_SIGNATURE_LEN];
+    sig_bytes[0] = 0x01;
+
+    let sig = Signature::try_from(&sig_bytes[..]).unwrap();
+    assert_eq!(sig.scheme(), 0x01);
+    assert_eq!(sig.raw(), &sig_bytes[1..]);
+
+    assert_eq!(Signature::try_from(&sig_bytes[..64]), Err(DecodeError::INPUT_TOO_SHORT));
+    assert_eq!(Signature::try_from(&[0u8; 0][..]), Err(DecodeError::INPUT_TOO_SHORT));
+    assert_eq!(
+        Signature::try_from(&[&sig_bytes[..], &[0u8][..]].concat()[..]),
+        Err(DecodeError::INPUT_TOO_LONG)
+    );
+}
@@ -0,0 +1,180 @@
+use elrond_wasm::{BoxedBytes, Vec};
+use elrond_wasm::elrond_codec::*;
+
+use zero_copy_derive::*;
+use zero_copy_sink::*;
+use zero_copy_source::*;
+
+const KIND_TOO_LARGE_ERR_MSG: &[u8] = b"kind too large";
+
+#[derive(ZeroCopyEncode, ZeroCopyDecode, Debug, PartialEq)]
+struct Message {
+    #[zero_copy(validate = "check_kind")]
+    kind: u16,
+    #[zero_copy(var_uint)]
+    nonce: u32,
+    #[zero_copy(fixed_len = 4)]
+    tag: BoxedBytes,
+    #[zero_copy(with = "reversed_bytes")]
+    payload: BoxedBytes,
+    extra: Option<u8>,
+    #[zero_copy(skip_partial)]
+    items: Vec<u32>,
+}
+
+fn check_kind(kind: &u16) -> Result<(), &'static [u8]> {
+    if *kind <= 0xff {
+        Ok(())
+    } else {
+        Err(KIND_TOO_LARGE_ERR_MSG)
+    }
+}
+
+mod reversed_bytes {
+    use super::*;
+
+    pub fn encode_to_sink<O: NestedEncodeOutput>(bytes: &BoxedBytes, sink: &mut O) -> Result<(), EncodeError> {
+        let mut reversed = bytes.as_slice().to_vec();
+        reversed.reverse();
+        sink.write_var_bytes(&reversed);
+
+        Ok(())
+    }
+
+    pub fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<BoxedBytes, DecodeError> {
+        let mut bytes = source.read_var_bytes()?.to_vec();
+        bytes.reverse();
+
+        Ok(BoxedBytes::from(bytes))
+    }
+}
+
+fn test_message() -> Message {
+    Message {
+        kind: 2,
+        nonce: 0x1234,
+        tag: BoxedBytes::from(&b"poly"[..]),
+        payload: BoxedBytes::from(&[1u8, 2, 3][..]),
+        extra: Some(7),
+        items: [5u32].to_vec(),
+    }
+}
+
+#[test]
+fn derived_layout_test() {
+    let message = test_message();
+
+    let mut serialized = Vec::new();
+    message.dep_encode(&mut serialized).unwrap();
+
+    let partial: &[u8] = &[
+        0x02, 0x00, // kind
+        0xfd, 0x34, 0x12, // nonce, as a var uint
+        b'p', b'o', b'l', b'y', // tag, without a length
+        0x03, 3, 2, 1, // payload, reversed
+        0x01, 7, // extra
+    ];
+    assert_eq!(&serialized[..partial.len()], partial);
+    assert_eq!(&serialized[partial.len()..], &[0x01, 5, 0, 0, 0]);
+    assert_eq!(message.get_partial_serialized().unwrap().as_slice(), partial);
+
+    assert_eq!(Message::top_decode(serialized.as_slice()), Ok(message));
+}
+
+#[test]
+fn derived_checks_test() {
+    let mut message = test_message();
+    let mut serialized = Vec::new();
+
+    message.kind = 0x100;
+    assert_eq!(message.dep_encode(&mut serialized), Err(EncodeError::from(KIND_TOO_LARGE_ERR_MSG)));
+
+    message.kind = 2;
+    message.tag = BoxedBytes::from(&b"too long"[..]);
+    serialized.clear();
+    assert_eq!(
+        message.dep_encode(&mut serialized),
+        Err(EncodeError::from(FIXED_LEN_MISMATCH_ERR_MSG))
+    );
+
+    message.tag = BoxedBytes::from(&b"poly"[..]);
+    serialized.clear();
+    message.dep_encode(&mut serialized).unwrap();
+
+    serialized[1] = 1;
+    assert_eq!(Message::top_decode(serialized.as_slice()), Err(DecodeError::from(KIND_TOO_LARGE_ERR_MSG)));
+
+    // the option tag must be 0 or 1
+    serialized[1] = 0;
+    serialized[13] = 2;
+    assert_eq!(Message::top_decode(serialized.as_slice()), Err(DecodeError::INVALID_VALUE));
+}
+
+#[test]
+fn derived_nested_decode_test() {
+    let message = test_message();
+
+    let mut serialized = Vec::new();
+    message.dep_encode(&mut serialized).unwrap();
+    let message_len = serialized.len();
+    0xabcdu16.dep_encode(&mut serialized).unwrap();
+
+    // only the message is read, the rest is left for the next value
+    let mut input = serialized.as_slice();
+    assert_eq!(Message::dep_decode(&mut input), Ok(test_message()));
+    assert_eq!(input, &serialized[message_len..]);
+    assert_eq!(u16::dep_decode(&mut input), Ok(0xabcd));
+
+    let pair = <(Message, u16)>::top_decode(serialized.as_slice()).unwrap();
+    assert_eq!(pair, (message, 0xabcd));
+}
+
+#[test]
+fn derived_partial_errors_test() {
+    let mut message = test_message();
+    message.tag = BoxedBytes::from(&b"too long"[..]);
+
+    assert_eq!(
+        message.get_partial_serialized(),
+        Err(EncodeError::from(FIXED_LEN_MISMATCH_ERR_MSG))
+    );
+}
+
+#[test]
+fn derived_decode_limits_test() {
+    let mut serialized = Vec::new();
+    test_message().dep_encode(&mut serialized).unwrap();
+
+    let limits = DecodeLimits {
+        max_bytes_len: 3,
+        max_list_len: 1,
+    };
+    assert_eq!(top_decode_with_limits(serialized.as_slice(), limits), Ok(test_message()));
+
+    // the 3-byte payload
+    let limits = DecodeLimits {
+        max_bytes_len: 2,
+        max_list_len: 1,
+    };
+    assert_eq!(
+        top_decode_with_limits::<Message, _>(serialized.as_slice(), limits),
+        Err(DecodeError::from(BYTES_TOO_LONG_ERR_MSG))
+    );
+
+    // the 1-item list
+    let limits = DecodeLimits {
+        max_bytes_len: 3,
+        max_list_len: 0,
+    };
+    assert_eq!(
+        top_decode_with_limits::<Message, _>(serialized.as_slice(), limits),
+        Err(DecodeError::from(LIST_TOO_LONG_ERR_MSG))
+    );
+
+    let mut input = serialized.as_slice();
+    let mut nested = ZeroCopyInput::with_limits(&mut input, limits);
+    assert_eq!(
+        Message::decode_from_source(&mut nested),
+        Err(DecodeError::from(LIST_TOO_LONG_ERR_MSG))
+    );
+}
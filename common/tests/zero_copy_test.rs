@@ -0,0 +1,155 @@
+extern crate zero_copy_sink;
+use zero_copy_sink::*;
+
+extern crate zero_copy_source;
+use zero_copy_source::*;
+
+use elrond_wasm::elrond_codec::*;
+
+use util::*;
+
+#[test]
+fn var_uint_round_trip_test() {
+    for val in [0u64, 0xfc, 0xfd, 0xffff, 0x10000, 0xffffffff, 0x100000000, u64::MAX].iter() {
+        let mut sink = ZeroCopySink::new();
+        sink.write_var_uint(*val);
+
+        let serialized = sink.get_sink();
+        let mut source = ZeroCopySource::new(serialized.as_slice());
+        assert_eq!(source.next_var_uint(), Ok(*val));
+        assert_eq!(source.get_bytes_left(), 0);
+    }
+}
+
+#[test]
+fn non_canonical_var_uint_test() {
+    let non_canonical: [&[u8]; 3] = [
+        &[0xfd, 0xfc, 0x00],
+        &[0xfe, 0xff, 0xff, 0x00, 0x00],
+        &[0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00],
+    ];
+
+    for bytes in non_canonical.iter() {
+        let mut source = ZeroCopySource::new(bytes);
+        assert_eq!(
+            source.next_var_uint(),
+            Err(DecodeError::from(NON_CANONICAL_VAR_UINT_ERR_MSG))
+        );
+    }
+
+    let mut source = ZeroCopySource::new(&[0xfe, 0x01, 0x00]);
+    assert_eq!(source.next_var_uint(), Err(DecodeError::INPUT_TOO_SHORT));
+}
+
+#[test]
+fn length_limits_test() {
+    let limits = DecodeLimits {
+        max_bytes_len: 4,
+        max_list_len: 2,
+    };
+
+    let mut source = ZeroCopySource::with_limits(&[0x05, 1, 2, 3, 4, 5], limits);
+    assert_eq!(
+        source.next_var_bytes(),
+        Err(DecodeError::from(BYTES_TOO_LONG_ERR_MSG))
+    );
+
+    let mut source = ZeroCopySource::with_limits(&[0x04, 1, 2, 3], limits);
+    assert_eq!(source.next_var_bytes(), Err(DecodeError::INPUT_TOO_SHORT));
+
+    let mut source = ZeroCopySource::with_limits(&[0x03, 1, 2, 3], limits);
+    assert_eq!(
+        source.next_list_len(),
+        Err(DecodeError::from(LIST_TOO_LONG_ERR_MSG))
+    );
+
+    // a huge count is rejected before anything is read
+    let mut source = ZeroCopySource::new(&[0xfe, 0x00, 0x00, 0x01, 0x00]);
+    assert_eq!(
+        source.next_list_len(),
+        Err(DecodeError::from(LIST_TOO_LONG_ERR_MSG))
+    );
+
+    let mut source = ZeroCopySource::with_limits(&[0x02, 1], limits);
+    assert_eq!(source.next_list_len(), Err(DecodeError::INPUT_TOO_SHORT));
+}
+
+#[test]
+fn borrowed_reads_test() {
+    let mut input = [0x77u8; 4 + 35 + 1];
+    input[..4].copy_from_slice(&[0x03, b'a', b'b', b'c']);
+    input[4..7].copy_from_slice(&[0x12, 0x05, 0x02]);
+    input[39] = 0x2a;
+
+    let mut source = ZeroCopySource::new(&input);
+
+    // byte strings are slices of the input
+    let bytes = source.next_var_bytes().unwrap();
+    assert_eq!(bytes, &b"abc"[..]);
+    assert_eq!(bytes.as_ptr(), input[1..].as_ptr());
+
+    // keys are decoded in place, then reading continues after them
+    let key = source.next_public_key().unwrap();
+    assert_eq!(key.to_bytes().as_slice(), &input[4..39]);
+    assert_eq!(source.next_u8(), Some(0x2a));
+    assert_eq!(source.expect_end(), Ok(()));
+}
+
+#[test]
+fn write_to_any_output_test() {
+    let mut sink = ZeroCopySink::new();
+    sink.write_u32(0x01020304);
+    sink.write_var_bytes(&[0xaa; 0xfd][..]);
+
+    // the same values, appended straight to an existing output
+    let mut output = vec![0x42u8];
+    output.write_u32(0x01020304);
+    output.write_var_bytes(&[0xaa; 0xfd][..]);
+
+    assert_eq!(&sink.as_slice()[..7], &[0x04, 0x03, 0x02, 0x01, 0xfd, 0xfd, 0x00]);
+    assert_eq!(&output[1..], sink.as_slice());
+    assert_eq!(sink.into_boxed_bytes().as_slice(), &output[1..]);
+}
+
+#[test]
+fn wide_primitives_round_trip_test() {
+    let wide = U256::from_be_bytes([0xabu8; 32]);
+
+    let mut sink = ZeroCopySink::new();
+    sink.write_i8(-2);
+    sink.write_i16(-300);
+    sink.write_i32(i32::MIN);
+    sink.write_i64(-1);
+    sink.write_u128(u128::MAX - 1);
+    sink.write_u256(&wide);
+    sink.write_array(&[7u8; 5]);
+    sink.write_bool(true);
+
+    let serialized = sink.into_boxed_bytes();
+    assert_eq!(serialized.len(), 1 + 2 + 4 + 8 + 16 + 32 + 5 + 1);
+    assert_eq!(&serialized.as_slice()[..3], &[0xfe, 0xd4, 0xfe]);
+
+    let mut source = ZeroCopySource::new(serialized.as_slice());
+    assert_eq!(source.next_i8(), Some(-2));
+    assert_eq!(source.next_i16(), Some(-300));
+    assert_eq!(source.next_i32(), Some(i32::MIN));
+    assert_eq!(source.next_i64(), Some(-1));
+    assert_eq!(source.next_u128(), Some(u128::MAX - 1));
+    assert_eq!(source.next_u256(), Some(wide));
+    assert_eq!(source.next_array::<5>(), Some([7u8; 5]));
+    assert_eq!(source.next_bool(), Ok(true));
+    assert_eq!(source.next_array::<1>(), None);
+}
+
+#[test]
+fn strict_bool_and_u256_test() {
+    assert_eq!(ZeroCopySource::new(&[0x00]).next_bool(), Ok(false));
+    assert_eq!(ZeroCopySource::new(&[0x02]).next_bool(), Err(DecodeError::INVALID_VALUE));
+    assert_eq!(ZeroCopySource::new(&[]).next_bool(), Err(DecodeError::INPUT_TOO_SHORT));
+
+    let small = U256::from(0x0102u128);
+    assert_eq!(small.to_le_bytes()[..3], [0x02, 0x01, 0x00]);
+    assert_eq!(small.to_be_bytes()[30..], [0x01, 0x02]);
+    assert_eq!(small.to_u128(), Some(0x0102));
+    assert_eq!(U256::from_be_bytes([0xffu8; 32]).to_u128(), None);
+}
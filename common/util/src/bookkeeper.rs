@@ -0,0 +1,51 @@
+// Poly derives the bookkeeper address of a consensus from its public keys,
+// as ripemd160(sha256(program)), where the program commits to the keys and the signature threshold.
+// sha256 is left to the caller, as it's provided by the Elrond API.
+
+use elrond_wasm::Vec;
+
+use super::PublicKey;
+
+// opcode checking a single signature
+const CHECKSIG: u8 = 0xac;
+
+/// The minimum number of signatures Poly requires from a consensus of _num_keys_
+pub fn bookkeeper_threshold(num_keys: usize) -> usize {
+    num_keys - (num_keys - 1) / 3
+}
+
+/// The program whose hash is the bookkeeper address of _keys_. Empty if there are no keys.
+pub fn bookkeeper_program(keys: &[PublicKey]) -> Vec<u8> {
+    let mut program = Vec::new();
+
+    if keys.len() == 1 {
+        let key = keys[0].to_compressed_bytes();
+
+        program.push(key.len() as u8); // push the key's bytes
+        program.extend_from_slice(key.as_slice());
+        program.push(CHECKSIG);
+
+        return program;
+    }
+    if keys.is_empty() {
+        return program;
+    }
+
+    // sorted by algorithm, curve, then coordinates, like Poly does
+    let mut sorted_keys: Vec<&PublicKey> = keys.iter().collect();
+    sorted_keys.sort_by(|a, b| {
+        (a.algorithm().as_u8(), a.curve().as_u8(), a.x_coordinate(), a.point())
+            .cmp(&(b.algorithm().as_u8(), b.curve().as_u8(), b.x_coordinate(), b.point()))
+    });
+
+    program.extend_from_slice(&(keys.len() as u16).to_le_bytes());
+    for key in sorted_keys {
+        let key = key.to_compressed_bytes();
+
+        program.push(key.len() as u8); // var bytes length, keys are always shorter than 0xfd
+        program.extend_from_slice(key.as_slice());
+    }
+    program.extend_from_slice(&(bookkeeper_threshold(keys.len()) as u16).to_le_bytes());
+
+    program
+}
@@ -1,58 +1,148 @@
 #![no_std]
 
-use elrond_wasm::{Box, derive_imports};
+use core::convert::TryFrom;
 
+use elrond_wasm::{Box, BoxedBytes, Vec, derive_imports};
+use elrond_wasm::elrond_codec::*;
+
+/// length of an uncompressed ECDSA/SM2 key on a 256-bit curve, as used by Poly's consensus
 pub const POLYCHAIN_PUBKEY_LEN: usize = 67;
 pub const POLYCHAIN_SIGNATURE_LEN: usize = 65;
+pub const POLYCHAIN_ADDRESS_LEN: usize = 20;
+/// default epoch length, used for chains that don't have one set
 pub const POLYCHAIN_EPOCH_HEIGHT: u32 = 60000;
 
-derive_imports!();
+pub mod bookkeeper;
+pub mod public_key;
+pub mod ripemd160;
+pub mod u256;
 
-#[derive(TypeAbi, Debug, PartialEq)]
-pub struct PublicKey(Box<[u8; POLYCHAIN_PUBKEY_LEN]>);
+pub use public_key::*;
+pub use u256::*;
+
+derive_imports!();
 
 #[derive(TypeAbi, Debug, PartialEq)]
 pub struct Signature(Box<[u8; POLYCHAIN_SIGNATURE_LEN]>);
 
-impl PublicKey {
+/// Poly/Ontology address, e.g. a header's next bookkeeper. Shorter than an Elrond Address.
+#[derive(TypeAbi, Debug, PartialEq, Clone)]
+pub struct PolyAddress([u8; POLYCHAIN_ADDRESS_LEN]);
+
+impl Signature {
     pub fn as_slice(&self) -> &[u8] {
         &(*self.0)[..]
     }
+
+    /// the signature scheme, e.g. SHA256withECDSA
+    pub fn scheme(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// the signature without its scheme, r || s for ECDSA
+    pub fn raw(&self) -> &[u8] {
+        &self.0[1..]
+    }
+
+    /// ECDSA signatures as r || s, DER-encoded
+    pub fn to_der(&self) -> BoxedBytes {
+        let (r, s) = self.raw().split_at(self.raw().len() / 2);
+        let r = der_integer(r);
+        let s = der_integer(s);
+
+        let mut der = Vec::with_capacity(2 + r.len() + s.len());
+        der.push(0x30); // sequence
+        der.push((r.len() + s.len()) as u8);
+        der.extend_from_slice(&r);
+        der.extend_from_slice(&s);
+
+        BoxedBytes::from(der.as_slice())
+    }
 }
 
-impl Signature {
+// big endian unsigned integer, without leading zeros, and with a 0 prefix if the first bit is set, so it's not negative
+fn der_integer(bytes: &[u8]) -> Vec<u8> {
+    let first_non_zero = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len() - 1);
+    let bytes = &bytes[first_non_zero..];
+
+    let mut integer = Vec::with_capacity(3 + bytes.len());
+    integer.push(0x02); // integer
+    if bytes[0] & 0x80 != 0 {
+        integer.push((bytes.len() + 1) as u8);
+        integer.push(0);
+    } else {
+        integer.push(bytes.len() as u8);
+    }
+    integer.extend_from_slice(bytes);
+
+    integer
+}
+
+impl PolyAddress {
+    pub fn zero() -> Self {
+        PolyAddress([0u8; POLYCHAIN_ADDRESS_LEN])
+    }
+
     pub fn as_slice(&self) -> &[u8] {
-        &(*self.0)[..]
+        &self.0[..]
     }
 }
 
-impl<'a> From<&'a [u8]> for PublicKey {
+impl From<[u8; POLYCHAIN_ADDRESS_LEN]> for PolyAddress {
     #[inline]
-    fn from(byte_slice: &'a [u8]) -> Self {
-        let mut data = [0u8; POLYCHAIN_PUBKEY_LEN];
+    fn from(bytes: [u8; POLYCHAIN_ADDRESS_LEN]) -> Self {
+        PolyAddress(bytes)
+    }
+}
+
+/// Fails unless _byte_slice_ is exactly one signature long
+impl<'a> TryFrom<&'a [u8]> for Signature {
+    type Error = DecodeError;
 
-        if byte_slice.len() >= POLYCHAIN_PUBKEY_LEN {
-            for i in 0..POLYCHAIN_PUBKEY_LEN {
-                data[i] = byte_slice[i];
-            }
+    fn try_from(byte_slice: &'a [u8]) -> Result<Self, Self::Error> {
+        if byte_slice.len() < POLYCHAIN_SIGNATURE_LEN {
+            return Err(DecodeError::INPUT_TOO_SHORT);
+        }
+        if byte_slice.len() > POLYCHAIN_SIGNATURE_LEN {
+            return Err(DecodeError::INPUT_TOO_LONG);
         }
 
-        PublicKey(Box::from(data))
+        let mut data = [0u8; POLYCHAIN_SIGNATURE_LEN];
+        data.copy_from_slice(byte_slice);
+
+        Ok(Signature(Box::from(data)))
     }
 }
 
-impl<'a> From<&'a [u8]> for Signature {
-    #[inline]
-    fn from(byte_slice: &'a [u8]) -> Self {
-        let mut data = [0u8; POLYCHAIN_SIGNATURE_LEN];
+// PolyAddress is encoded as its raw bytes, as it has a fixed length
 
-        if byte_slice.len() >= POLYCHAIN_SIGNATURE_LEN {
-            for i in 0..POLYCHAIN_SIGNATURE_LEN {
-                data[i] = byte_slice[i];
-            }
-        }
+impl NestedEncode for PolyAddress {
+    fn dep_encode<O: NestedEncodeOutput>(&self, dest: &mut O) -> Result<(), EncodeError> {
+        dest.write(self.as_slice());
+
+        Ok(())
+    }
+}
+
+impl NestedDecode for PolyAddress {
+    fn dep_decode<I: NestedDecodeInput>(input: &mut I) -> Result<Self, DecodeError> {
+        let mut bytes = [0u8; POLYCHAIN_ADDRESS_LEN];
+        input.read_into(&mut bytes)?;
 
-        Signature(Box::from(data))
+        Ok(PolyAddress(bytes))
+    }
+}
+
+impl TopEncode for PolyAddress {
+    #[inline]
+    fn top_encode<O: TopEncodeOutput>(&self, output: O) -> Result<(), EncodeError> {
+        top_encode_from_nested(self, output)
+    }
+}
+
+impl TopDecode for PolyAddress {
+    fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
+        top_decode_from_nested(input)
     }
 }
 
@@ -88,4 +178,31 @@ pub mod hex_converter {
 
         BoxedBytes::from(hex.as_slice())
     }
+
+    pub fn hex_digit_to_half_byte(digit: u8) -> Option<u8> {
+        match digit {
+            b'0'..=b'9' => Some(digit - b'0'),
+            b'a'..=b'f' => Some(digit - b'a' + 0xau8),
+            b'A'..=b'F' => Some(digit - b'A' + 0xau8),
+            _ => None,
+        }
+    }
+
+    /// None if the length is odd or there are non-hex characters
+    pub fn hex_to_byte_slice(hex: &[u8]) -> Option<BoxedBytes> {
+        if !hex.len().is_multiple_of(2) {
+            return None;
+        }
+
+        let mut bytes = Vec::with_capacity(hex.len() / 2);
+
+        for pair in hex.chunks(2) {
+            let digit1 = hex_digit_to_half_byte(pair[0])?;
+            let digit2 = hex_digit_to_half_byte(pair[1])?;
+
+            bytes.push((digit1 << 4) | digit2);
+        }
+
+        Some(BoxedBytes::from(bytes.as_slice()))
+    }
 }
@@ -0,0 +1,225 @@
+use elrond_wasm::{BoxedBytes, Vec, derive_imports};
+use elrond_wasm::elrond_codec::*;
+
+derive_imports!();
+
+/// Poly/Ontology key algorithms, serialized as the first byte of a key
+#[derive(TypeAbi, Debug, PartialEq, Clone, Copy)]
+pub enum KeyAlgorithm {
+    Ecdsa,
+    Sm2,
+    EdDsa,
+}
+
+/// Curve labels, serialized as the second byte of a key
+#[derive(TypeAbi, Debug, PartialEq, Clone, Copy)]
+pub enum Curve {
+    P224,
+    P256,
+    P384,
+    P521,
+    Secp256k1,
+    Sm2P256V1,
+    Ed25519,
+}
+
+impl KeyAlgorithm {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x12 => Some(KeyAlgorithm::Ecdsa),
+            0x13 => Some(KeyAlgorithm::Sm2),
+            0x14 => Some(KeyAlgorithm::EdDsa),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            KeyAlgorithm::Ecdsa => 0x12,
+            KeyAlgorithm::Sm2 => 0x13,
+            KeyAlgorithm::EdDsa => 0x14,
+        }
+    }
+}
+
+impl Curve {
+    pub fn from_u8(label: u8) -> Option<Self> {
+        match label {
+            1 => Some(Curve::P224),
+            2 => Some(Curve::P256),
+            3 => Some(Curve::P384),
+            4 => Some(Curve::P521),
+            5 => Some(Curve::Secp256k1),
+            20 => Some(Curve::Sm2P256V1),
+            25 => Some(Curve::Ed25519),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Curve::P224 => 1,
+            Curve::P256 => 2,
+            Curve::P384 => 3,
+            Curve::P521 => 4,
+            Curve::Secp256k1 => 5,
+            Curve::Sm2P256V1 => 20,
+            Curve::Ed25519 => 25,
+        }
+    }
+
+    /// length of a coordinate for elliptic curves, of the whole key for Ed25519
+    pub fn coordinate_len(&self) -> usize {
+        match self {
+            Curve::P224 => 28,
+            Curve::P256 | Curve::Secp256k1 | Curve::Sm2P256V1 | Curve::Ed25519 => 32,
+            Curve::P384 => 48,
+            Curve::P521 => 66,
+        }
+    }
+
+    pub fn is_valid_for(&self, algorithm: KeyAlgorithm) -> bool {
+        match algorithm {
+            KeyAlgorithm::Ecdsa => matches!(
+                self,
+                Curve::P224 | Curve::P256 | Curve::P384 | Curve::P521 | Curve::Secp256k1
+            ),
+            KeyAlgorithm::Sm2 => *self == Curve::Sm2P256V1,
+            KeyAlgorithm::EdDsa => *self == Curve::Ed25519,
+        }
+    }
+}
+
+// elliptic curve point prefixes, as in SEC 1
+const POINT_UNCOMPRESSED: u8 = 0x04;
+const POINT_COMPRESSED_EVEN: u8 = 0x02;
+const POINT_COMPRESSED_ODD: u8 = 0x03;
+
+/// A Poly public key, serialized as algorithm, curve label, then the point.
+/// ECDSA and SM2 points may be compressed or not. Ed25519 keys are the raw 32 bytes.
+#[derive(TypeAbi, Debug, PartialEq)]
+pub struct PublicKey {
+    algorithm: KeyAlgorithm,
+    curve: Curve,
+    point: BoxedBytes,
+}
+
+impl PublicKey {
+    /// Fails if _bytes_ is not exactly one well-formed key
+    pub fn try_from_slice(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut input = bytes;
+        let key = Self::dep_decode(&mut input)?;
+
+        if !input.is_empty() {
+            return Err(DecodeError::INPUT_TOO_LONG);
+        }
+
+        Ok(key)
+    }
+
+    pub fn algorithm(&self) -> KeyAlgorithm {
+        self.algorithm
+    }
+
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    /// the point, as it was received
+    pub fn point(&self) -> &[u8] {
+        self.point.as_slice()
+    }
+
+    /// the X coordinate for elliptic curves, the whole key for Ed25519
+    pub fn x_coordinate(&self) -> &[u8] {
+        match self.algorithm {
+            KeyAlgorithm::EdDsa => self.point(),
+            _ => &self.point()[1..1 + self.curve.coordinate_len()],
+        }
+    }
+
+    pub fn compressed_point(&self) -> BoxedBytes {
+        if self.algorithm == KeyAlgorithm::EdDsa || self.point()[0] != POINT_UNCOMPRESSED {
+            return BoxedBytes::from(self.point());
+        }
+
+        let y_parity = self.point()[self.point().len() - 1] & 1;
+
+        let mut compressed = Vec::with_capacity(1 + self.curve.coordinate_len());
+        compressed.push(POINT_COMPRESSED_EVEN + y_parity);
+        compressed.extend_from_slice(self.x_coordinate());
+
+        BoxedBytes::from(compressed.as_slice())
+    }
+
+    /// the key as it was received
+    pub fn to_bytes(&self) -> BoxedBytes {
+        BoxedBytes::from_concat(&[
+            &[self.algorithm.as_u8(), self.curve.as_u8()][..],
+            self.point(),
+        ])
+    }
+
+    /// the key as Poly serializes it, with a compressed point
+    pub fn to_compressed_bytes(&self) -> BoxedBytes {
+        BoxedBytes::from_concat(&[
+            &[self.algorithm.as_u8(), self.curve.as_u8()][..],
+            self.compressed_point().as_slice(),
+        ])
+    }
+}
+
+// the key's length is known from its algorithm, curve and point prefix, so keys can be decoded from the middle of the input
+
+impl NestedEncode for PublicKey {
+    fn dep_encode<O: NestedEncodeOutput>(&self, dest: &mut O) -> Result<(), EncodeError> {
+        dest.write(self.to_bytes().as_slice());
+
+        Ok(())
+    }
+}
+
+impl NestedDecode for PublicKey {
+    fn dep_decode<I: NestedDecodeInput>(input: &mut I) -> Result<Self, DecodeError> {
+        let algorithm = match KeyAlgorithm::from_u8(u8::dep_decode(input)?) {
+            Some(algorithm) => algorithm,
+            None => return Err(DecodeError::INVALID_VALUE),
+        };
+        let curve = match Curve::from_u8(u8::dep_decode(input)?) {
+            Some(curve) if curve.is_valid_for(algorithm) => curve,
+            _ => return Err(DecodeError::INVALID_VALUE),
+        };
+
+        let point = if algorithm == KeyAlgorithm::EdDsa {
+            BoxedBytes::from(input.read_slice(curve.coordinate_len())?)
+        } else {
+            let prefix = u8::dep_decode(input)?;
+            let coordinates_len = match prefix {
+                POINT_UNCOMPRESSED => 2 * curve.coordinate_len(),
+                POINT_COMPRESSED_EVEN | POINT_COMPRESSED_ODD => curve.coordinate_len(),
+                _ => return Err(DecodeError::INVALID_VALUE),
+            };
+
+            BoxedBytes::from_concat(&[&[prefix][..], input.read_slice(coordinates_len)?])
+        };
+
+        Ok(PublicKey {
+            algorithm,
+            curve,
+            point,
+        })
+    }
+}
+
+impl TopEncode for PublicKey {
+    #[inline]
+    fn top_encode<O: TopEncodeOutput>(&self, output: O) -> Result<(), EncodeError> {
+        top_encode_from_nested(self, output)
+    }
+}
+
+impl TopDecode for PublicKey {
+    fn top_decode<I: TopDecodeInput>(input: I) -> Result<Self, DecodeError> {
+        top_decode_from_nested(input)
+    }
+}
@@ -0,0 +1,59 @@
+// 256-bit integers, as in Poly's Go codec and EVM payloads. Only conversions, no arithmetic.
+
+use elrond_wasm::derive_imports;
+
+derive_imports!();
+
+pub const U256_LEN: usize = 32;
+
+/// 256-bit unsigned integer, stored as little endian bytes
+#[derive(TypeAbi, Debug, PartialEq, Clone, Copy, Default)]
+pub struct U256([u8; U256_LEN]);
+
+impl U256 {
+    pub fn zero() -> Self {
+        U256([0u8; U256_LEN])
+    }
+
+    pub fn from_le_bytes(bytes: [u8; U256_LEN]) -> Self {
+        U256(bytes)
+    }
+
+    pub fn from_be_bytes(mut bytes: [u8; U256_LEN]) -> Self {
+        bytes.reverse();
+
+        U256(bytes)
+    }
+
+    pub fn to_le_bytes(&self) -> [u8; U256_LEN] {
+        self.0
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; U256_LEN] {
+        let mut bytes = self.0;
+        bytes.reverse();
+
+        bytes
+    }
+
+    /// None if the value doesn't fit
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.0[16..].iter().any(|byte| *byte != 0) {
+            return None;
+        }
+
+        let mut low = [0u8; 16];
+        low.copy_from_slice(&self.0[..16]);
+
+        Some(u128::from_le_bytes(low))
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(val: u128) -> Self {
+        let mut bytes = [0u8; U256_LEN];
+        bytes[..16].copy_from_slice(&val.to_le_bytes());
+
+        U256(bytes)
+    }
+}
@@ -0,0 +1,314 @@
+//! Derives ZeroCopyEncode and ZeroCopyDecode for structs, field by field, in declaration order.
+//! Both derives also implement the Elrond codec traits (NestedEncode/TopEncode and NestedDecode/TopDecode) through them.
+//! NestedDecode reads straight from the codec input, TopDecode uses the default DecodeLimits.
+//! Other limits can be used through `zero_copy_source::top_decode_with_limits` and `ZeroCopyInput::with_limits`.
+//!
+//! Fields use their type's own ZeroCopyEncode/ZeroCopyDecode implementation, unless changed by an attribute:
+//! - `#[zero_copy(var_uint)]` - an integer serialized as a var uint
+//! - `#[zero_copy(fixed_len = N)]` - exactly N bytes, without a length prefix, e.g. a BoxedBytes field
+//! - `#[zero_copy(with = "path")]` - serialized by `path::encode_to_sink` and `path::decode_from_source`,
+//!   which take any NestedEncodeOutput and ZeroCopyRead
+//! - `#[zero_copy(validate = "path")]` - checked by `path(&field) -> Result<(), &'static [u8]>`
+//!   after the field is decoded and before it's encoded, the error message is returned as the codec error
+//! - `#[zero_copy(skip_partial)]` - left out of the partial serialization
+//!
+//! If any field is marked skip_partial, ZeroCopyEncode also adds `encode_partial_to_sink` and `get_partial_serialized`,
+//! which serialize the other fields, e.g. to be hashed. Fields aren't validated in the partial serialization.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, Lit, Meta, NestedMeta, Path, Type};
+
+#[proc_macro_derive(ZeroCopyEncode, attributes(zero_copy))]
+pub fn derive_zero_copy_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match encode_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(ZeroCopyDecode, attributes(zero_copy))]
+pub fn derive_zero_copy_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match decode_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+enum Layout {
+    Default,
+    VarUint,
+    FixedLen(usize),
+    With(Path),
+}
+
+struct FieldInfo {
+    name: Ident,
+    ty: Type,
+    layout: Layout,
+    validate: Option<Path>,
+    skip_partial: bool,
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<FieldInfo>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(&input.ident, "only structs with named fields are supported")),
+        },
+        _ => return Err(syn::Error::new_spanned(&input.ident, "only structs are supported")),
+    };
+
+    fields.iter().map(parse_field).collect()
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<FieldInfo> {
+    let mut info = FieldInfo {
+        name: field.ident.clone().unwrap(),
+        ty: field.ty.clone(),
+        layout: Layout::Default,
+        validate: None,
+        skip_partial: false,
+    };
+
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("zero_copy")) {
+        let items = match attr.parse_meta()? {
+            Meta::List(list) => list.nested,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[zero_copy(...)]")),
+        };
+
+        for item in items {
+            match &item {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("var_uint") => {
+                    set_layout(&mut info, Layout::VarUint, &item)?;
+                },
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip_partial") => {
+                    info.skip_partial = true;
+                },
+                NestedMeta::Meta(Meta::NameValue(name_value)) => match &name_value.lit {
+                    Lit::Int(len) if name_value.path.is_ident("fixed_len") => {
+                        set_layout(&mut info, Layout::FixedLen(len.base10_parse()?), &item)?;
+                    },
+                    Lit::Str(path) if name_value.path.is_ident("with") => {
+                        set_layout(&mut info, Layout::With(path.parse()?), &item)?;
+                    },
+                    Lit::Str(path) if name_value.path.is_ident("validate") => {
+                        info.validate = Some(path.parse()?);
+                    },
+                    _ => return Err(syn::Error::new_spanned(&item, "unknown zero_copy attribute")),
+                },
+                _ => return Err(syn::Error::new_spanned(&item, "unknown zero_copy attribute")),
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+fn set_layout(info: &mut FieldInfo, layout: Layout, item: &NestedMeta) -> syn::Result<()> {
+    if let Layout::Default = info.layout {
+        info.layout = layout;
+
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(item, "only one of var_uint, fixed_len and with can be used"))
+    }
+}
+
+fn encode_field(field: &FieldInfo, validate: bool) -> TokenStream2 {
+    let name = &field.name;
+
+    let check = match (&field.validate, validate) {
+        (Some(path), true) => quote! {
+            if let core::result::Result::Err(msg) = #path(&self.#name) {
+                return core::result::Result::Err(elrond_wasm::elrond_codec::EncodeError::from(msg));
+            }
+        },
+        _ => quote! {},
+    };
+
+    let write = match &field.layout {
+        Layout::Default => quote! {
+            zero_copy_sink::ZeroCopyEncode::encode_to_sink(&self.#name, sink)?;
+        },
+        Layout::VarUint => quote! {
+            zero_copy_sink::ZeroCopyWrite::write_var_uint(sink, self.#name as u64);
+        },
+        Layout::FixedLen(len) => quote! {
+            if self.#name.len() != #len {
+                return core::result::Result::Err(
+                    elrond_wasm::elrond_codec::EncodeError::from(zero_copy_sink::FIXED_LEN_MISMATCH_ERR_MSG));
+            }
+            zero_copy_sink::ZeroCopyWrite::write_bytes(sink, self.#name.as_slice());
+        },
+        Layout::With(path) => quote! {
+            #path::encode_to_sink(&self.#name, sink)?;
+        },
+    };
+
+    quote! {
+        #check
+        #write
+    }
+}
+
+fn decode_field(field: &FieldInfo) -> TokenStream2 {
+    let name = &field.name;
+    let ty = &field.ty;
+
+    let read = match &field.layout {
+        Layout::Default => quote! {
+            <#ty as zero_copy_source::ZeroCopyDecode>::decode_from_source(source)?
+        },
+        Layout::VarUint => quote! {
+            match core::convert::TryFrom::try_from(zero_copy_source::ZeroCopyRead::next_var_uint(source)?) {
+                core::result::Result::Ok(val) => val,
+                core::result::Result::Err(_) => {
+                    return core::result::Result::Err(elrond_wasm::elrond_codec::DecodeError::INVALID_VALUE)
+                },
+            }
+        },
+        Layout::FixedLen(len) => quote! {
+            <#ty as core::convert::From<&[u8]>>::from(
+                elrond_wasm::elrond_codec::NestedDecodeInput::read_slice(source, #len)?)
+        },
+        Layout::With(path) => quote! {
+            #path::decode_from_source(source)?
+        },
+    };
+
+    let check = match &field.validate {
+        Some(path) => quote! {
+            if let core::result::Result::Err(msg) = #path(&#name) {
+                return core::result::Result::Err(elrond_wasm::elrond_codec::DecodeError::from(msg));
+            }
+        },
+        None => quote! {},
+    };
+
+    quote! {
+        let #name: #ty = #read;
+        #check
+    }
+}
+
+fn encode_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = parse_fields(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let encode_fields = fields.iter().map(|field| encode_field(field, true));
+
+    let partial = if fields.iter().any(|field| field.skip_partial) {
+        let encode_partial_fields = fields
+            .iter()
+            .filter(|field| !field.skip_partial)
+            .map(|field| encode_field(field, false));
+
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                pub fn encode_partial_to_sink<O: elrond_wasm::elrond_codec::NestedEncodeOutput>(
+                    &self,
+                    sink: &mut O,
+                ) -> core::result::Result<(), elrond_wasm::elrond_codec::EncodeError> {
+                    #(#encode_partial_fields)*
+
+                    core::result::Result::Ok(())
+                }
+
+                pub fn get_partial_serialized(
+                    &self,
+                ) -> core::result::Result<elrond_wasm::BoxedBytes, elrond_wasm::elrond_codec::EncodeError> {
+                    let mut sink = zero_copy_sink::ZeroCopySink::new();
+                    self.encode_partial_to_sink(&mut sink)?;
+
+                    core::result::Result::Ok(sink.into_boxed_bytes())
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics zero_copy_sink::ZeroCopyEncode for #name #ty_generics #where_clause {
+            fn encode_to_sink<O: elrond_wasm::elrond_codec::NestedEncodeOutput>(
+                &self,
+                sink: &mut O,
+            ) -> core::result::Result<(), elrond_wasm::elrond_codec::EncodeError> {
+                #(#encode_fields)*
+
+                core::result::Result::Ok(())
+            }
+        }
+
+        impl #impl_generics elrond_wasm::elrond_codec::NestedEncode for #name #ty_generics #where_clause {
+            fn dep_encode<O: elrond_wasm::elrond_codec::NestedEncodeOutput>(
+                &self,
+                dest: &mut O,
+            ) -> core::result::Result<(), elrond_wasm::elrond_codec::EncodeError> {
+                zero_copy_sink::ZeroCopyEncode::encode_to_sink(self, dest)
+            }
+        }
+
+        impl #impl_generics elrond_wasm::elrond_codec::TopEncode for #name #ty_generics #where_clause {
+            #[inline]
+            fn top_encode<O: elrond_wasm::elrond_codec::TopEncodeOutput>(
+                &self,
+                output: O,
+            ) -> core::result::Result<(), elrond_wasm::elrond_codec::EncodeError> {
+                elrond_wasm::elrond_codec::top_encode_from_nested(self, output)
+            }
+        }
+
+        #partial
+    })
+}
+
+fn decode_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = parse_fields(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let decode_fields = fields.iter().map(decode_field);
+    let field_names = fields.iter().map(|field| &field.name);
+
+    Ok(quote! {
+        impl #impl_generics zero_copy_source::ZeroCopyDecode for #name #ty_generics #where_clause {
+            fn decode_from_source<S: zero_copy_source::ZeroCopyRead>(
+                source: &mut S,
+            ) -> core::result::Result<Self, elrond_wasm::elrond_codec::DecodeError> {
+                #(#decode_fields)*
+
+                core::result::Result::Ok(#name {
+                    #(#field_names),*
+                })
+            }
+        }
+
+        impl #impl_generics elrond_wasm::elrond_codec::NestedDecode for #name #ty_generics #where_clause {
+            fn dep_decode<I: elrond_wasm::elrond_codec::NestedDecodeInput>(
+                input: &mut I,
+            ) -> core::result::Result<Self, elrond_wasm::elrond_codec::DecodeError> {
+                let mut source = zero_copy_source::ZeroCopyInput::new(input);
+
+                <Self as zero_copy_source::ZeroCopyDecode>::decode_from_source(&mut source)
+            }
+        }
+
+        impl #impl_generics elrond_wasm::elrond_codec::TopDecode for #name #ty_generics #where_clause {
+            fn top_decode<I: elrond_wasm::elrond_codec::TopDecodeInput>(
+                input: I,
+            ) -> core::result::Result<Self, elrond_wasm::elrond_codec::DecodeError> {
+                zero_copy_source::top_decode_from_source(input)
+            }
+        }
+    })
+}
@@ -1,20 +1,29 @@
 #![no_std]
 
-use elrond_wasm::{Address, BoxedBytes, H256, Vec};
+use elrond_wasm::{Address, BoxedBytes, H256, TokenIdentifier, Vec};
 use elrond_wasm::elrond_codec::*;
 use util::*;
 
+pub const FIXED_LEN_MISMATCH_ERR_MSG: &[u8] = b"fixed-size field has the wrong length";
+
+/// An owned buffer, for when the serialized bytes are needed on their own, e.g. to be hashed.
+/// Anything else should encode straight into its NestedEncodeOutput.
 pub struct ZeroCopySink {
     sink: Vec<u8>
 }
 
 impl NestedEncodeOutput for ZeroCopySink {
     fn write(&mut self, bytes: &[u8]) {
-        self.write_bytes(bytes);
+        self.sink.extend_from_slice(bytes);
+    }
+}
+
+impl Default for ZeroCopySink {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-// little endian encoding is used
 impl ZeroCopySink {
     pub fn new() -> Self {
         ZeroCopySink {
@@ -22,20 +31,33 @@ impl ZeroCopySink {
         }
     }
 
+    pub fn as_slice(&self) -> &[u8] {
+        self.sink.as_slice()
+    }
+
     pub fn get_sink(&self) -> BoxedBytes {
         BoxedBytes::from(self.sink.as_slice())
     }
 
-    pub fn write_bytes(&mut self, bytes: &[u8]) {
-        self.sink.extend_from_slice(bytes);
+    pub fn into_boxed_bytes(self) -> BoxedBytes {
+        BoxedBytes::from(self.sink)
+    }
+}
+
+/// Poly's serialization of basic values, available for any NestedEncodeOutput,
+/// so nested values are written straight into the final output.
+/// Little endian encoding is used.
+pub trait ZeroCopyWrite: NestedEncodeOutput + Sized {
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write(bytes);
     }
 
-    pub fn write_u8(&mut self, byte: u8) {
-        self.sink.push(byte);
+    fn write_u8(&mut self, byte: u8) {
+        self.push_byte(byte);
     }
 
-    pub fn write_bool(&mut self, boolean: bool) {
-        if boolean == true {
+    fn write_bool(&mut self, boolean: bool) {
+        if boolean {
             self.write_u8(1u8);
         }
         else {
@@ -43,22 +65,48 @@ impl ZeroCopySink {
         }
     }
 
-    pub fn write_u16(&mut self, val: u16) {
-        self.write_u8(val as u8);
-        self.write_u8((val >> 8) as u8);
+    fn write_u16(&mut self, val: u16) {
+        self.write(&val.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, val: u32) {
+        self.write(&val.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, val: u64) {
+        self.write(&val.to_le_bytes());
     }
 
-    pub fn write_u32(&mut self, val: u32) {
-        self.write_u16(val as u16);
-        self.write_u16((val >> 16) as u16)
+    fn write_u128(&mut self, val: u128) {
+        self.write(&val.to_le_bytes());
     }
 
-    pub fn write_u64(&mut self, val: u64) {
-        self.write_u32(val as u32);
-        self.write_u32((val >> 32) as u32);
+    fn write_u256(&mut self, val: &U256) {
+        self.write(&val.to_le_bytes());
     }
 
-    pub fn write_var_uint(&mut self, val: u64) {
+    fn write_i8(&mut self, val: i8) {
+        self.write(&val.to_le_bytes());
+    }
+
+    fn write_i16(&mut self, val: i16) {
+        self.write(&val.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, val: i32) {
+        self.write(&val.to_le_bytes());
+    }
+
+    fn write_i64(&mut self, val: i64) {
+        self.write(&val.to_le_bytes());
+    }
+
+    /// The bytes as they are, the length is known from the type
+    fn write_array<const N: usize>(&mut self, array: &[u8; N]) {
+        self.write(array);
+    }
+
+    fn write_var_uint(&mut self, val: u64) {
         if val < 0xfd {
             self.write_u8(val as u8);
         }
@@ -66,7 +114,7 @@ impl ZeroCopySink {
             self.write_u8(0xfd);
             self.write_u16(val as u16);
         }
-        else if val <= 0xffffff {
+        else if val <= 0xffffffff {
             self.write_u8(0xfe);
             self.write_u32(val as u32);
         }
@@ -76,24 +124,132 @@ impl ZeroCopySink {
         }
     }
 
-    pub fn write_var_bytes(&mut self, bytes: &[u8]) {
+    fn write_var_bytes(&mut self, bytes: &[u8]) {
         self.write_var_uint(bytes.len() as u64);
         self.write_bytes(bytes);
     }
 
-    pub fn write_address(&mut self, address: &Address) {
+    fn write_address(&mut self, address: &Address) {
         self.write_bytes(address.as_bytes());
     }
 
-    pub fn write_hash(&mut self, hash: &H256) {
+    fn write_poly_address(&mut self, address: &PolyAddress) {
+        self.write_bytes(address.as_slice());
+    }
+
+    fn write_hash(&mut self, hash: &H256) {
         self.write_bytes(hash.as_bytes());
     }
 
-    pub fn write_public_key(&mut self, key: &PublicKey) {
-        self.write_bytes(key.as_slice());
+    fn write_public_key(&mut self, key: &PublicKey) {
+        let _ = key.dep_encode(self);
     }
 
-    pub fn write_signature(&mut self, sig: &Signature) {
+    fn write_signature(&mut self, sig: &Signature) {
         self.write_bytes(sig.as_slice());
     }
 }
+
+impl<O: NestedEncodeOutput> ZeroCopyWrite for O {}
+
+/// Types with a Poly serialization. Structs usually derive it, see the zero-copy-derive crate.
+pub trait ZeroCopyEncode {
+    fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError>;
+}
+
+macro_rules! encode_with {
+    ($ty:ty, $write:ident) => {
+        impl ZeroCopyEncode for $ty {
+            fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+                sink.$write(*self);
+
+                Ok(())
+            }
+        }
+    };
+}
+
+encode_with!(u8, write_u8);
+encode_with!(u16, write_u16);
+encode_with!(u32, write_u32);
+encode_with!(u64, write_u64);
+encode_with!(u128, write_u128);
+encode_with!(i8, write_i8);
+encode_with!(i16, write_i16);
+encode_with!(i32, write_i32);
+encode_with!(i64, write_i64);
+encode_with!(bool, write_bool);
+
+macro_rules! encode_ref_with {
+    ($ty:ty, $write:ident) => {
+        impl ZeroCopyEncode for $ty {
+            fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+                sink.$write(self);
+
+                Ok(())
+            }
+        }
+    };
+}
+
+encode_ref_with!(H256, write_hash);
+encode_ref_with!(U256, write_u256);
+encode_ref_with!(Address, write_address);
+encode_ref_with!(PolyAddress, write_poly_address);
+encode_ref_with!(PublicKey, write_public_key);
+encode_ref_with!(Signature, write_signature);
+
+impl<const N: usize> ZeroCopyEncode for [u8; N] {
+    fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+        sink.write_array(self);
+
+        Ok(())
+    }
+}
+
+// byte strings are prefixed by their length
+
+impl ZeroCopyEncode for BoxedBytes {
+    fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+        sink.write_var_bytes(self.as_slice());
+
+        Ok(())
+    }
+}
+
+impl ZeroCopyEncode for TokenIdentifier {
+    fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+        sink.write_var_bytes(self.as_slice());
+
+        Ok(())
+    }
+}
+
+/// The number of items as a var uint, then the items
+impl<T: ZeroCopyEncode> ZeroCopyEncode for Vec<T> {
+    fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+        sink.write_var_uint(self.len() as u64);
+        for item in self {
+            item.encode_to_sink(sink)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// byte(0) if the value does not exist, byte(1) followed by the value if it does
+impl<T: ZeroCopyEncode> ZeroCopyEncode for Option<T> {
+    fn encode_to_sink<O: NestedEncodeOutput>(&self, sink: &mut O) -> Result<(), EncodeError> {
+        match self {
+            Some(val) => {
+                sink.write_u8(1);
+                val.encode_to_sink(sink)
+            },
+            None => {
+                sink.write_u8(0);
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,32 +1,58 @@
 #![no_std]
 
-use elrond_wasm::{Address, BoxedBytes, H256, Vec};
+use core::convert::TryFrom;
+
+use elrond_wasm::{Address, BoxedBytes, H256, TokenIdentifier, Vec};
 use elrond_wasm::elrond_codec::*;
 
 use util::*;
 
+pub const NON_CANONICAL_VAR_UINT_ERR_MSG: &[u8] = b"non-canonical var uint";
+pub const BYTES_TOO_LONG_ERR_MSG: &[u8] = b"byte string too long";
+pub const LIST_TOO_LONG_ERR_MSG: &[u8] = b"list too long";
+
+pub const DEFAULT_MAX_BYTES_LEN: usize = 0x10000;
+pub const DEFAULT_MAX_LIST_LEN: usize = 0x400;
+
+/// Upper bounds for the lengths read from the input, so malformed input fails before anything is allocated
+#[derive(Clone, Copy)]
+pub struct DecodeLimits {
+    pub max_bytes_len: usize,
+    pub max_list_len: usize
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_bytes_len: DEFAULT_MAX_BYTES_LEN,
+            max_list_len: DEFAULT_MAX_LIST_LEN
+        }
+    }
+}
+
+/// Reads values from a borrowed buffer, without copying it.
+/// Byte strings are returned as slices of the input, fixed-size values are read directly into their targets.
 #[derive(Clone)]
-pub struct ZeroCopySource {
-    source: Vec<u8>,
-    index: usize
+pub struct ZeroCopySource<'a> {
+    source: &'a [u8],
+    index: usize,
+    limits: DecodeLimits
 }
 
-impl NestedDecodeInput for ZeroCopySource {
+impl<'a> NestedDecodeInput for ZeroCopySource<'a> {
     fn remaining_len(&mut self) -> usize {
         self.get_bytes_left()
     }
 
     fn read_into(&mut self, into: &mut [u8]) -> Result<(), DecodeError> {
-        if self.get_bytes_left() >= into.len() {
-        
-            for i in 0..into.len() {
-                into[i] = self.source[self.index + i];
-            }
+        match self.next_bytes(into.len()) {
+            Some(slice) => {
+                into.copy_from_slice(slice);
 
-            return Ok(());
+                Ok(())
+            },
+            None => Err(DecodeError::INPUT_TOO_SHORT)
         }
-
-        return Err(DecodeError::INPUT_TOO_SHORT)
     }
 
     fn read_into_or_exit<ExitCtx: Clone>(
@@ -35,22 +61,15 @@ impl NestedDecodeInput for ZeroCopySource {
 		c: ExitCtx,
 		exit: fn(ExitCtx, DecodeError) -> !,
 	) {
-        let result = self.read_into(into);
-
-        if result.is_err() {
-            exit(c, result.unwrap_err());
+        if let Err(err) = self.read_into(into) {
+            exit(c, err);
         }
     }
 
     fn read_slice(&mut self, length: usize) -> Result<&[u8], DecodeError> {
-        if self.get_bytes_left() >= length {
-            let slice = &self.source[self.index..(self.index + length)];
-            self.index += length;
-
-            Ok(slice)
-        }
-        else {
-            Err(DecodeError::INPUT_TOO_SHORT)
+        match self.next_bytes(length) {
+            Some(slice) => Ok(slice),
+            None => Err(DecodeError::INPUT_TOO_SHORT)
         }
     }
 
@@ -60,13 +79,9 @@ impl NestedDecodeInput for ZeroCopySource {
 		c: ExitCtx,
 		exit: fn(ExitCtx, DecodeError) -> !,
 	) -> &[u8] {
-        let result = self.read_slice(length);
-
-        if result.is_ok() {
-            result.unwrap()
-        }
-        else {
-            exit(c, result.unwrap_err());
+        match self.next_bytes(length) {
+            Some(slice) => slice,
+            None => exit(c, DecodeError::INPUT_TOO_SHORT)
         }
     }
 
@@ -79,154 +94,388 @@ impl NestedDecodeInput for ZeroCopySource {
     }
 }
 
-// little endian encoding is used
-impl ZeroCopySource {
-    pub fn new(source: &[u8]) -> Self {
-        let mut src = Vec::new();
-        src.extend_from_slice(source);
+/// Top-level decoding with the default limits, see top_decode_with_limits
+pub fn top_decode_from_source<T: ZeroCopyDecode, I: TopDecodeInput>(input: I) -> Result<T, DecodeError> {
+    top_decode_with_limits(input, DecodeLimits::default())
+}
+
+/// Top-level decoding for types decoded from a ZeroCopySource, which only accepts lengths within _limits_.
+/// Fails if input is left over after decoding, so trailing bytes can't be appended to a valid value.
+pub fn top_decode_with_limits<T: ZeroCopyDecode, I: TopDecodeInput>(
+    input: I,
+    limits: DecodeLimits
+) -> Result<T, DecodeError> {
+    let bytes = input.into_boxed_slice_u8();
+    let mut source = ZeroCopySource::with_limits(&bytes, limits);
+    let result = T::decode_from_source(&mut source)?;
+    source.expect_end()?;
+
+    Ok(result)
+}
+
+impl<'a> ZeroCopySource<'a> {
+    pub fn new(source: &'a [u8]) -> Self {
+        Self::with_limits(source, DecodeLimits::default())
+    }
 
+    pub fn with_limits(source: &'a [u8], limits: DecodeLimits) -> Self {
         ZeroCopySource {
-            source: src,
-            index: 0
+            source,
+            index: 0,
+            limits
         }
     }
 
-    pub fn get_source(&self) -> BoxedBytes {
-        BoxedBytes::from(self.source.as_slice())
+    pub fn get_source(&self) -> &'a [u8] {
+        self.source
     }
 
     pub fn get_bytes_left(&self) -> usize {
         self.source.len() - self.index
     }
 
-    pub fn next_bytes(&mut self, len: usize) -> Option<BoxedBytes> {
+    /// Fails if any input is left to be read
+    pub fn expect_end(&self) -> Result<(), DecodeError> {
+        if self.get_bytes_left() > 0 {
+            return Err(DecodeError::INPUT_TOO_LONG);
+        }
+
+        Ok(())
+    }
+
+    pub fn next_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
         if self.get_bytes_left() >= len {
-            let boxed = BoxedBytes::from(&self.source[self.index..(self.index + len)]);
+            let slice = &self.source[self.index..(self.index + len)];
             self.index += len;
 
-            Some(boxed)
+            Some(slice)
         }
         else {
             None
         }
     }
 
-    pub fn next_u8(&mut self) -> Option<u8> {
-        let size_u8 = core::mem::size_of::<u8>();
-        if self.get_bytes_left() >= size_u8 {
-            let val = self.source[self.index];
-            self.index += size_u8;
+    /// Same as ZeroCopyRead::read_var_bytes, but the result borrows the underlying buffer, not the source itself
+    pub fn next_var_bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.next_var_bytes_len()?;
 
-            Some(val)
+        match self.next_bytes(len) {
+            Some(bytes) => Ok(bytes),
+            None => Err(DecodeError::INPUT_TOO_SHORT)
         }
-        else {
-            None
+    }
+}
+
+impl<'a> ZeroCopyRead for ZeroCopySource<'a> {
+    fn limits(&self) -> DecodeLimits {
+        self.limits
+    }
+}
+
+/// Makes any other codec input a ZeroCopyRead, e.g. the input of a NestedDecode implementation.
+/// Values are read straight from the wrapped input, so whatever is not read is left in it.
+pub struct ZeroCopyInput<'i, I: NestedDecodeInput> {
+    input: &'i mut I,
+    limits: DecodeLimits
+}
+
+impl<'i, I: NestedDecodeInput> ZeroCopyInput<'i, I> {
+    pub fn new(input: &'i mut I) -> Self {
+        Self::with_limits(input, DecodeLimits::default())
+    }
+
+    pub fn with_limits(input: &'i mut I, limits: DecodeLimits) -> Self {
+        ZeroCopyInput {
+            input,
+            limits
         }
     }
+}
+
+impl<'i, I: NestedDecodeInput> NestedDecodeInput for ZeroCopyInput<'i, I> {
+    fn remaining_len(&mut self) -> usize {
+        self.input.remaining_len()
+    }
+
+    fn read_into(&mut self, into: &mut [u8]) -> Result<(), DecodeError> {
+        self.input.read_into(into)
+    }
 
-    pub fn next_bool(&mut self) -> Option<bool> {
+    fn read_into_or_exit<ExitCtx: Clone>(
+		&mut self,
+		into: &mut [u8],
+		c: ExitCtx,
+		exit: fn(ExitCtx, DecodeError) -> !,
+	) {
+        self.input.read_into_or_exit(into, c, exit)
+    }
+
+    fn read_slice(&mut self, length: usize) -> Result<&[u8], DecodeError> {
+        self.input.read_slice(length)
+    }
+
+    fn read_slice_or_exit<ExitCtx: Clone>(
+		&mut self,
+		length: usize,
+		c: ExitCtx,
+		exit: fn(ExitCtx, DecodeError) -> !,
+	) -> &[u8] {
+        self.input.read_slice_or_exit(length, c, exit)
+    }
+
+    fn flush(&mut self) -> &[u8] {
+        self.input.flush()
+    }
+}
+
+impl<'i, I: NestedDecodeInput> ZeroCopyRead for ZeroCopyInput<'i, I> {
+    fn limits(&self) -> DecodeLimits {
+        self.limits
+    }
+}
+
+/// Reads the Poly serialization from a codec input, whose lengths must be within the input's limits.
+/// Little endian encoding is used.
+pub trait ZeroCopyRead: NestedDecodeInput + Sized {
+    fn limits(&self) -> DecodeLimits;
+
+    /// Fills _target_ with the next bytes. Returns false if there are not enough bytes left.
+    fn next_into(&mut self, target: &mut [u8]) -> bool {
+        self.read_into(target).is_ok()
+    }
+
+    fn next_u8(&mut self) -> Option<u8> {
+        self.read_byte().ok()
+    }
+
+    /// Only 0 and 1 are valid
+    fn next_bool(&mut self) -> Result<bool, DecodeError> {
         match self.next_u8() {
-            Some(val) => {
-                if val == 1 {
-                    Some(true)
-                }
-                else if val == 0 {
-                    Some(false)
-                }
-                else {
-                    None
-                }
-            }
-            None => None
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            Some(_) => Err(DecodeError::INVALID_VALUE),
+            None => Err(DecodeError::INPUT_TOO_SHORT)
         }
     }
 
-    pub fn next_u16(&mut self) -> Option<u16> {
-        if self.get_bytes_left() >= core::mem::size_of::<u16>() {
-            let b0 = self.next_u8().unwrap() as u16;
-            let b1 = self.next_u8().unwrap() as u16;
+    fn next_u16(&mut self) -> Option<u16> {
+        self.next_array::<2>().map(u16::from_le_bytes)
+    }
+
+    fn next_u32(&mut self) -> Option<u32> {
+        self.next_array::<4>().map(u32::from_le_bytes)
+    }
+
+    fn next_u64(&mut self) -> Option<u64> {
+        self.next_array::<8>().map(u64::from_le_bytes)
+    }
+
+    fn next_u128(&mut self) -> Option<u128> {
+        self.next_array::<16>().map(u128::from_le_bytes)
+    }
 
-            Some((b1 << 8) | b0)
+    fn next_i8(&mut self) -> Option<i8> {
+        self.next_array::<1>().map(i8::from_le_bytes)
+    }
+
+    fn next_i16(&mut self) -> Option<i16> {
+        self.next_array::<2>().map(i16::from_le_bytes)
+    }
+
+    fn next_i32(&mut self) -> Option<i32> {
+        self.next_array::<4>().map(i32::from_le_bytes)
+    }
+
+    fn next_i64(&mut self) -> Option<i64> {
+        self.next_array::<8>().map(i64::from_le_bytes)
+    }
+
+    fn next_u256(&mut self) -> Option<U256> {
+        self.next_array::<U256_LEN>().map(U256::from_le_bytes)
+    }
+
+    fn next_array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let mut array = [0u8; N];
+
+        if self.next_into(&mut array) {
+            Some(array)
         }
         else {
             None
         }
     }
 
-    pub fn next_u32(&mut self) -> Option<u32> {
-        if self.get_bytes_left() >= core::mem::size_of::<u32>() {
-            let b10 = self.next_u16().unwrap() as u32;
-            let b32 = self.next_u16().unwrap() as u32;
+    /// Only the shortest encoding of a value is accepted, so the same value can't be serialized in multiple ways
+    fn next_var_uint(&mut self) -> Result<u64, DecodeError> {
+        let (val, min_val) = match self.next_u8() {
+            Some(0xfd) => (self.next_u16().map(|val| val as u64), 0xfd),
+            Some(0xfe) => (self.next_u32().map(|val| val as u64), 0x10000),
+            Some(0xff) => (self.next_u64(), 0x100000000),
+            Some(len_id) => (Some(len_id as u64), 0),
+            None => (None, 0)
+        };
+
+        match val {
+            Some(val) if val >= min_val => Ok(val),
+            Some(_) => Err(DecodeError::from(NON_CANONICAL_VAR_UINT_ERR_MSG)),
+            None => Err(DecodeError::INPUT_TOO_SHORT)
+        }
+    }
+
+    /// The length prefix of a byte string, checked against the limits
+    fn next_var_bytes_len(&mut self) -> Result<usize, DecodeError> {
+        let len = self.next_var_uint()?;
 
-            Some((b32 << 16) | b10)
+        if len > self.limits().max_bytes_len as u64 {
+            return Err(DecodeError::from(BYTES_TOO_LONG_ERR_MSG));
+        }
+
+        Ok(len as usize)
+    }
+
+    fn read_var_bytes(&mut self) -> Result<&[u8], DecodeError> {
+        let len = self.next_var_bytes_len()?;
+
+        self.read_slice(len)
+    }
+
+    /// Every item takes at least one byte, so lists longer than the remaining input are rejected right away
+    fn next_list_len(&mut self) -> Result<usize, DecodeError> {
+        let len = self.next_var_uint()?;
+
+        if len > self.limits().max_list_len as u64 {
+            return Err(DecodeError::from(LIST_TOO_LONG_ERR_MSG));
+        }
+        if len > self.remaining_len() as u64 {
+            return Err(DecodeError::INPUT_TOO_SHORT);
+        }
+
+        Ok(len as usize)
+    }
+
+    fn next_address(&mut self) -> Option<Address> {
+        let mut address = Address::zero();
+
+        if self.next_into(address.as_mut()) {
+            Some(address)
         }
         else {
             None
         }
     }
 
-    pub fn next_u64(&mut self) -> Option<u64> {
-        if self.get_bytes_left() >= core::mem::size_of::<u64>() {
-            let b3210 = self.next_u32().unwrap() as u64;
-            let b7654 = self.next_u32().unwrap() as u64;
+    fn next_poly_address(&mut self) -> Option<PolyAddress> {
+        self.next_array::<POLYCHAIN_ADDRESS_LEN>().map(PolyAddress::from)
+    }
+
+    fn next_hash(&mut self) -> Option<H256> {
+        let mut hash = H256::zero();
 
-            Some((b7654 << 32) | b3210)
+        if self.next_into(hash.as_mut()) {
+            Some(hash)
         }
         else {
             None
         }
     }
 
-    pub fn next_var_uint(&mut self) -> Option<u64> {
-        let opt_len_id = self.next_u8();
+    fn next_public_key(&mut self) -> Result<PublicKey, DecodeError> {
+        PublicKey::dep_decode(self)
+    }
+
+    fn next_signature(&mut self) -> Option<Signature> {
+        self.read_slice(POLYCHAIN_SIGNATURE_LEN)
+            .ok()
+            .and_then(|bytes| Signature::try_from(bytes).ok())
+    }
+}
+
+/// Types with a Poly serialization. Structs usually derive it, see the zero-copy-derive crate.
+pub trait ZeroCopyDecode: Sized {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError>;
+}
 
-        match opt_len_id {
-            Some(len_id) => {
-                match len_id {
-                    0xfd => self.next_u16().map(|val| val as u64),
-                    0xfe => self.next_u32().map(|val| val as u64),
-                    0xff => self.next_u64(),
-                    _ => Some(len_id as u64)
+macro_rules! decode_with {
+    ($ty:ty, $next:ident) => {
+        impl ZeroCopyDecode for $ty {
+            fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+                match source.$next() {
+                    Some(val) => Ok(val),
+                    None => Err(DecodeError::INPUT_TOO_SHORT)
                 }
             }
-            None => None
         }
-    }
+    };
+}
 
-    pub fn next_var_bytes(&mut self) -> Option<BoxedBytes> {
-        let opt_len = self.next_var_uint();
+decode_with!(u8, next_u8);
+decode_with!(u16, next_u16);
+decode_with!(u32, next_u32);
+decode_with!(u64, next_u64);
+decode_with!(u128, next_u128);
+decode_with!(i8, next_i8);
+decode_with!(i16, next_i16);
+decode_with!(i32, next_i32);
+decode_with!(i64, next_i64);
+decode_with!(U256, next_u256);
+decode_with!(H256, next_hash);
+decode_with!(Address, next_address);
+decode_with!(PolyAddress, next_poly_address);
+decode_with!(Signature, next_signature);
+
+impl ZeroCopyDecode for bool {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        source.next_bool()
+    }
+}
 
-        match opt_len {
-            Some(len) => self.next_bytes(len as usize),
-            None => None
+impl<const N: usize> ZeroCopyDecode for [u8; N] {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        match source.next_array() {
+            Some(array) => Ok(array),
+            None => Err(DecodeError::INPUT_TOO_SHORT)
         }
     }
+}
 
-    pub fn next_address(&mut self) -> Option<Address> {
-        match self.next_bytes(Address::len_bytes()) {
-            Some(address_bytes) => Some(Address::from_slice(address_bytes.as_slice())),
-            None => None
-        }
+impl ZeroCopyDecode for PublicKey {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        source.next_public_key()
     }
+}
 
-    pub fn next_hash(&mut self) -> Option<H256> {
-        match self.next_bytes(H256::len_bytes()) {
-            Some(hash_bytes) => Some(H256::from_slice(hash_bytes.as_slice())),
-            None => None
-        }
+impl ZeroCopyDecode for BoxedBytes {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        source.read_var_bytes().map(BoxedBytes::from)
+    }
+}
+
+impl ZeroCopyDecode for TokenIdentifier {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        source.read_var_bytes().map(TokenIdentifier::from)
     }
+}
+
+impl<T: ZeroCopyDecode> ZeroCopyDecode for Vec<T> {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        let len = source.next_list_len()?;
 
-    pub fn next_public_key(&mut self) -> Option<PublicKey> {
-        match self.next_bytes(POLYCHAIN_PUBKEY_LEN) {
-            Some(key) => Some(PublicKey::from(key.as_slice())),
-            None => None
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::decode_from_source(source)?);
         }
+
+        Ok(items)
     }
+}
 
-    pub fn next_signature(&mut self) -> Option<Signature> {
-        match self.next_bytes(POLYCHAIN_SIGNATURE_LEN) {
-            Some(sig) => Some(Signature::from(sig.as_slice())),
-            None => None
+impl<T: ZeroCopyDecode> ZeroCopyDecode for Option<T> {
+    fn decode_from_source<S: ZeroCopyRead>(source: &mut S) -> Result<Self, DecodeError> {
+        match source.next_u8() {
+            Some(0) => Ok(None),
+            Some(1) => T::decode_from_source(source).map(Some),
+            Some(_) => Err(DecodeError::INVALID_VALUE),
+            None => Err(DecodeError::INPUT_TOO_SHORT)
         }
     }
 }
@@ -7,25 +7,31 @@ use cross_chain_management::*;
 extern crate esdt_token_manager;
 use esdt_token_manager::*;
 
-use elrond_wasm::*;
 use elrond_wasm_debug::*;
 
+mod test_api;
+use test_api::*;
+
+// the contracts run on a TestContext, as the scenarios need Ed25519 verification and synchronous calls
 fn contract_map() -> ContractMap<TxContext> {
 	let mut contract_map = ContractMap::new();
 
-	contract_map.register_contract(
+	register_contract(
+		&mut contract_map,
 		"file:../BlockHeaderSync/output/block-header-sync.wasm",
-		Box::new(|context| Box::new(BlockHeaderSyncImpl::new(context))),
+		|context| Box::new(BlockHeaderSyncImpl::new(context)),
 	);
 
-	contract_map.register_contract(
+	register_contract(
+		&mut contract_map,
 		"file:../CrossChainManagement/output/cross-chain-management.wasm",
-		Box::new(|context| Box::new(CrossChainManagementImpl::new(context))),
+		|context| Box::new(CrossChainManagementImpl::new(context)),
 	);
 
-	contract_map.register_contract(
+	register_contract(
+		&mut contract_map,
 		"file:../EsdtTokenManager/output/esdt-token-manager.wasm",
-		Box::new(|context| Box::new(EsdtTokenManagerImpl::new(context))),
+		|context| Box::new(EsdtTokenManagerImpl::new(context)),
 	);
 
 	contract_map
@@ -38,3 +44,275 @@ fn deploy() {
 		&contract_map(),
 	);
 }
+
+#[test]
+fn relayer_registry() {
+	parse_execute_mandos(
+		"mandos/relayer-registry.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn transfer_limits() {
+	parse_execute_mandos(
+		"mandos/transfer-limits.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn pause() {
+	parse_execute_mandos(
+		"mandos/pause.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_timestamps() {
+	parse_execute_mandos(
+		"mandos/header-timestamps.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_sync_views() {
+	parse_execute_mandos(
+		"mandos/header-sync-views.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_verifiers() {
+	parse_execute_mandos(
+		"mandos/header-verifiers.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn poly_vbft_genesis() {
+	parse_execute_mandos(
+		"mandos/poly-vbft-genesis.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn consensus_changes_rejected() {
+	parse_execute_mandos(
+		"mandos/consensus-changes-rejected.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn vrf_checks() {
+	parse_execute_mandos(
+		"mandos/vrf-checks.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_timestamp_checks() {
+	parse_execute_mandos(
+		"mandos/header-timestamp-checks.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn static_multisig_rejected() {
+	parse_execute_mandos(
+		"mandos/static-multisig-rejected.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn chain_config_validation() {
+	parse_execute_mandos(
+		"mandos/chain-config-validation.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_signers_rejected() {
+	parse_execute_mandos(
+		"mandos/header-signers-rejected.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn consensus_peers() {
+	parse_execute_mandos(
+		"mandos/consensus-peers.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_records() {
+	parse_execute_mandos(
+		"mandos/header-records.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_sync_views_populated() {
+	parse_execute_mandos(
+		"mandos/header-sync-views-populated.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn inbound_tx_proof() {
+	parse_execute_mandos(
+		"mandos/inbound-tx-proof.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn chain_trust_checks() {
+	parse_execute_mandos(
+		"mandos/chain-trust-checks.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn outbound_tx_not_processed() {
+	parse_execute_mandos(
+		"mandos/outbound-tx-not-processed.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn transfer_limits_rolling_window() {
+	parse_execute_mandos(
+		"mandos/transfer-limits-rolling-window.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn relayer_rewards() {
+	parse_execute_mandos(
+		"mandos/relayer-rewards.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn consensus_changes() {
+	parse_execute_mandos(
+		"mandos/consensus-changes.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_timestamps_accepted() {
+	parse_execute_mandos(
+		"mandos/header-timestamps-accepted.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn static_multisig() {
+	parse_execute_mandos(
+		"mandos/static-multisig.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_signers() {
+	parse_execute_mandos(
+		"mandos/header-signers.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_pruning() {
+	parse_execute_mandos(
+		"mandos/header-pruning.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn fork_detection() {
+	parse_execute_mandos(
+		"mandos/fork-detection.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn fork_rollback() {
+	parse_execute_mandos(
+		"mandos/fork-rollback.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn header_sync_rewards() {
+	parse_execute_mandos(
+		"mandos/header-sync-rewards.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn process_cross_chain_tx() {
+	parse_execute_mandos(
+		"mandos/process-cross-chain-tx.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn inbound_tx_chain_trust() {
+	parse_execute_mandos(
+		"mandos/inbound-tx-chain-trust.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn inbound_transfer_limits() {
+	parse_execute_mandos(
+		"mandos/inbound-transfer-limits.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn inbound_relayer_rewards() {
+	parse_execute_mandos(
+		"mandos/inbound-relayer-rewards.scen.json",
+		&contract_map(),
+	);
+}
+
+#[test]
+fn relayer_slashing() {
+	parse_execute_mandos(
+		"mandos/relayer-slashing.scen.json",
+		&contract_map(),
+	);
+}
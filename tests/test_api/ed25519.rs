@@ -0,0 +1,187 @@
+// Ed25519 signature verification as in RFC 8032, section 5.1.7, for the scenarios' signed headers.
+// It only needs to be correct, not fast or constant time, so it uses big integer arithmetic mod p.
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+
+const KEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+// the base point, encoded
+const BASE_POINT: [u8; KEY_LEN] = [
+	0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+	0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+
+struct Curve {
+	p: BigUint,
+	d: BigUint,
+	sqrt_minus_one: BigUint,
+	order: BigUint,
+}
+
+// extended coordinates, x = X/Z, y = Y/Z, x * y = T/Z
+#[derive(Clone)]
+struct Point {
+	x: BigUint,
+	y: BigUint,
+	z: BigUint,
+	t: BigUint,
+}
+
+impl Curve {
+	fn new() -> Self {
+		let p = (BigUint::from(1u32) << 255) - BigUint::from(19u32);
+		let d = (&p - BigUint::from(121665u32)) * inverse(&BigUint::from(121666u32), &p) % &p;
+		let sqrt_minus_one = BigUint::from(2u32).modpow(&((&p - BigUint::from(1u32)) >> 2), &p);
+		let order = (BigUint::from(1u32) << 252)
+			+ BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10).unwrap();
+
+		Curve {
+			p,
+			d,
+			sqrt_minus_one,
+			order,
+		}
+	}
+
+	fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+		(a + &self.p - b) % &self.p
+	}
+
+	fn identity(&self) -> Point {
+		Point {
+			x: BigUint::from(0u32),
+			y: BigUint::from(1u32),
+			z: BigUint::from(1u32),
+			t: BigUint::from(0u32),
+		}
+	}
+
+	// the unified addition formula, which also doubles
+	fn add(&self, a: &Point, b: &Point) -> Point {
+		let p = &self.p;
+		let aa = self.sub(&a.y, &a.x) * self.sub(&b.y, &b.x) % p;
+		let bb = (&a.y + &a.x) * (&b.y + &b.x) % p;
+		let cc = &a.t * BigUint::from(2u32) * &self.d * &b.t % p;
+		let dd = &a.z * BigUint::from(2u32) * &b.z % p;
+		let e = self.sub(&bb, &aa);
+		let f = self.sub(&dd, &cc);
+		let g = (&dd + &cc) % p;
+		let h = (&bb + &aa) % p;
+
+		Point {
+			x: &e * &f % p,
+			y: &g * &h % p,
+			z: &f * &g % p,
+			t: &e * &h % p,
+		}
+	}
+
+	fn mul(&self, scalar: &BigUint, point: &Point) -> Point {
+		let mut result = self.identity();
+		for i in (0..scalar.bits()).rev() {
+			result = self.add(&result, &result);
+			if scalar.bit(i) {
+				result = self.add(&result, point);
+			}
+		}
+
+		result
+	}
+
+	fn equals(&self, a: &Point, b: &Point) -> bool {
+		let p = &self.p;
+
+		&a.x * &b.z % p == &b.x * &a.z % p && &a.y * &b.z % p == &b.y * &a.z % p
+	}
+
+	// None if _bytes_ is not a point on the curve
+	fn decode(&self, bytes: &[u8]) -> Option<Point> {
+		let p = &self.p;
+		let x_sign = bytes[KEY_LEN - 1] >> 7;
+		let mut y_bytes = bytes.to_vec();
+		y_bytes[KEY_LEN - 1] &= 0x7f;
+		let y = BigUint::from_bytes_le(&y_bytes);
+		if &y >= p {
+			return None;
+		}
+
+		// x^2 = (y^2 - 1) / (d * y^2 + 1)
+		let y2 = &y * &y % p;
+		let u = self.sub(&y2, &BigUint::from(1u32));
+		let v = (&self.d * &y2 + BigUint::from(1u32)) % p;
+		let x2 = u * inverse(&v, p) % p;
+
+		let mut x = x2.modpow(&((p + BigUint::from(3u32)) >> 3), p);
+		if &x * &x % p != x2 {
+			x = x * &self.sqrt_minus_one % p;
+		}
+		if &x * &x % p != x2 {
+			return None;
+		}
+
+		let zero = BigUint::from(0u32);
+		if x == zero && x_sign == 1 {
+			return None;
+		}
+		if x.bit(0) != (x_sign == 1) {
+			x = p - x;
+		}
+
+		Some(Point {
+			t: &x * &y % p,
+			x,
+			y,
+			z: BigUint::from(1u32),
+		})
+	}
+}
+
+fn inverse(value: &BigUint, p: &BigUint) -> BigUint {
+	value.modpow(&(p - BigUint::from(2u32)), p)
+}
+
+pub fn verify(key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+	if key.len() != KEY_LEN || signature.len() != SIGNATURE_LEN {
+		return false;
+	}
+
+	let curve = Curve::new();
+	let (a, r) = match (curve.decode(key), curve.decode(&signature[..32])) {
+		(Some(a), Some(r)) => (a, r),
+		_ => return false,
+	};
+	let s = BigUint::from_bytes_le(&signature[32..]);
+	if s >= curve.order {
+		return false;
+	}
+
+	let mut hasher = Sha512::new();
+	hasher.update(&signature[..32]);
+	hasher.update(key);
+	hasher.update(message);
+	let k = BigUint::from_bytes_le(&hasher.finalize()) % &curve.order;
+
+	let base_point = curve.decode(&BASE_POINT).unwrap();
+
+	curve.equals(&curve.mul(&s, &base_point), &curve.add(&r, &curve.mul(&k, &a)))
+}
+
+#[test]
+fn ed25519_rfc8032_test() {
+	// RFC 8032, section 7.1, test 2
+	let key = hex::decode("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c").unwrap();
+	let signature = hex::decode(
+		"92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+		 085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+	)
+	.unwrap();
+
+	assert!(verify(&key, &[0x72], &signature));
+	assert!(!verify(&key, &[0x73], &signature));
+
+	let mut tampered = signature.clone();
+	tampered[40] ^= 1;
+	assert!(!verify(&key, &[0x72], &tampered));
+}
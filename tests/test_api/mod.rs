@@ -0,0 +1,437 @@
+// The debug mock doesn't implement verify_ed25519 or execute_on_dest_context, which the scenarios with signed headers
+// and inbound transactions need. TestContext wraps the mock's TxContext and delegates everything else to it:
+// - Ed25519 signatures are verified as in RFC 8032
+// - synchronous calls execute the destination contract against the storage of its latest successful transaction,
+//   so the called endpoints must not write to storage, as their writes are discarded
+
+mod ed25519;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use elrond_wasm::api::*;
+use elrond_wasm::types::{Address, ArgBuffer, BoxedBytes, CodeMetadata, H256, TokenIdentifier};
+use elrond_wasm_debug::api::{RustBigInt, RustBigUint};
+use elrond_wasm_debug::*;
+
+pub type ContractFactory = fn(TestContext) -> Box<dyn CallableContract<TestContext>>;
+
+// the state a synchronous call runs against
+struct CommittedContract {
+	factory: ContractFactory,
+	owner: Option<Address>,
+	storage: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+thread_local! {
+	static COMMITTED_CONTRACTS: RefCell<HashMap<Address, CommittedContract>> = RefCell::new(HashMap::new());
+}
+
+/// Registers a contract that runs on a TestContext. Forgets the contracts committed by previous scenarios.
+pub fn register_contract(contract_map: &mut ContractMap<TxContext>, path: &str, factory: ContractFactory) {
+	COMMITTED_CONTRACTS.with(|contracts| contracts.borrow_mut().clear());
+
+	contract_map.register_contract(
+		path,
+		Box::new(move |context| {
+			Box::new(TestContract {
+				inner: factory(TestContext(context)),
+				factory,
+			})
+		}),
+	);
+}
+
+struct TestContract {
+	inner: Box<dyn CallableContract<TestContext>>,
+	factory: ContractFactory,
+}
+
+impl CallableContract<TxContext> for TestContract {
+	fn call(&self, fn_name: &[u8]) -> bool {
+		self.inner.call(fn_name)
+	}
+
+	fn clone_contract(&self) -> Box<dyn CallableContract<TxContext>> {
+		Box::new(TestContract {
+			inner: self.inner.clone_contract(),
+			factory: self.factory,
+		})
+	}
+
+	// only called once the transaction succeeded, failed ones panic before
+	fn into_api(self: Box<Self>) -> TxContext {
+		let context = self.inner.into_api().0;
+		let committed = CommittedContract {
+			factory: self.factory,
+			owner: context.blockchain_info_box.contract_owner.clone(),
+			storage: context.tx_output_cell.borrow().contract_storage.clone(),
+		};
+		COMMITTED_CONTRACTS.with(|contracts| {
+			contracts
+				.borrow_mut()
+				.insert(context.tx_input_box.to.clone(), committed)
+		});
+
+		context
+	}
+}
+
+#[derive(Clone)]
+pub struct TestContext(TxContext);
+
+impl TestContext {
+	fn execute_sync_call(&self, address: &Address, value: &RustBigUint, function: &[u8], arg_buffer: &ArgBuffer) {
+		let (factory, owner, storage) = COMMITTED_CONTRACTS.with(|contracts| {
+			let contracts = contracts.borrow();
+			let contract = contracts
+				.get(address)
+				.unwrap_or_else(|| panic!("no contract was committed at the destination address"));
+
+			(contract.factory, contract.owner.clone(), contract.storage.clone())
+		});
+
+		let mut blockchain_info = (*self.0.blockchain_info_box).clone();
+		blockchain_info.contract_balance = 0u32.into();
+		blockchain_info.contract_esdt = HashMap::new();
+		blockchain_info.contract_owner = owner;
+
+		let tx_input = TxInput {
+			from: self.0.tx_input_box.to.clone(),
+			to: address.clone(),
+			call_value: value.value(),
+			esdt_value: 0u32.into(),
+			esdt_token_name: Vec::new(),
+			func_name: function.to_vec(),
+			args: arguments(arg_buffer),
+			gas_limit: self.0.tx_input_box.gas_limit,
+			gas_price: 0,
+			tx_hash: self.0.tx_input_box.tx_hash.clone(),
+		};
+		let tx_output = TxOutput {
+			contract_storage: storage,
+			..TxOutput::default()
+		};
+
+		// errors panic, and fail the calling transaction with the called contract's message
+		let contract = factory(TestContext(TxContext::new(blockchain_info, tx_input, tx_output)));
+		if !contract.call(function) {
+			std::panic::panic_any(TxPanic {
+				status: 1,
+				message: b"invalid function (not found)".to_vec(),
+			});
+		}
+	}
+}
+
+// ArgBuffer only exposes its contents as raw pointers, for the VM hooks
+fn arguments(arg_buffer: &ArgBuffer) -> Vec<Vec<u8>> {
+	let lengths = unsafe {
+		std::slice::from_raw_parts(arg_buffer.arg_lengths_bytes_ptr() as *const usize, arg_buffer.num_args())
+	};
+	let data = unsafe { std::slice::from_raw_parts(arg_buffer.arg_data_ptr(), lengths.iter().sum()) };
+
+	let mut args = Vec::with_capacity(lengths.len());
+	let mut start = 0;
+	for len in lengths {
+		args.push(data[start..start + len].to_vec());
+		start += len;
+	}
+
+	args
+}
+
+impl ContractHookApi<RustBigInt, RustBigUint> for TestContext {
+	type Storage = TxContext;
+	type CallValue = TxContext;
+	type SendApi = Self;
+
+	fn get_storage_raw(&self) -> Self::Storage {
+		self.0.clone()
+	}
+
+	fn call_value(&self) -> Self::CallValue {
+		self.0.clone()
+	}
+
+	fn send(&self) -> Self::SendApi {
+		self.clone()
+	}
+
+	fn get_sc_address(&self) -> Address {
+		self.0.get_sc_address()
+	}
+
+	fn get_owner_address(&self) -> Address {
+		self.0.get_owner_address()
+	}
+
+	fn get_caller(&self) -> Address {
+		self.0.get_caller()
+	}
+
+	fn get_balance(&self, address: &Address) -> RustBigUint {
+		self.0.get_balance(address)
+	}
+
+	fn get_tx_hash(&self) -> H256 {
+		self.0.get_tx_hash()
+	}
+
+	fn get_gas_left(&self) -> u64 {
+		self.0.get_gas_left()
+	}
+
+	fn get_block_timestamp(&self) -> u64 {
+		self.0.get_block_timestamp()
+	}
+
+	fn get_block_nonce(&self) -> u64 {
+		self.0.get_block_nonce()
+	}
+
+	fn get_block_round(&self) -> u64 {
+		self.0.get_block_round()
+	}
+
+	fn get_block_epoch(&self) -> u64 {
+		self.0.get_block_epoch()
+	}
+
+	fn get_block_random_seed(&self) -> Box<[u8; 48]> {
+		self.0.get_block_random_seed()
+	}
+
+	fn get_prev_block_timestamp(&self) -> u64 {
+		self.0.get_prev_block_timestamp()
+	}
+
+	fn get_prev_block_nonce(&self) -> u64 {
+		self.0.get_prev_block_nonce()
+	}
+
+	fn get_prev_block_round(&self) -> u64 {
+		self.0.get_prev_block_round()
+	}
+
+	fn get_prev_block_epoch(&self) -> u64 {
+		self.0.get_prev_block_epoch()
+	}
+
+	fn get_prev_block_random_seed(&self) -> Box<[u8; 48]> {
+		self.0.get_prev_block_random_seed()
+	}
+}
+
+impl CryptoApi for TestContext {
+	fn sha256(&self, data: &[u8]) -> H256 {
+		self.0.sha256(data)
+	}
+
+	fn keccak256(&self, data: &[u8]) -> H256 {
+		self.0.keccak256(data)
+	}
+
+	fn verify_bls(&self, key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+		self.0.verify_bls(key, message, signature)
+	}
+
+	fn verify_ed25519(&self, key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+		ed25519::verify(key, message, signature)
+	}
+
+	fn verify_secp256k1(&self, key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+		self.0.verify_secp256k1(key, message, signature)
+	}
+}
+
+impl SendApi<RustBigUint> for TestContext {
+	fn direct_egld(&self, to: &Address, amount: &RustBigUint, data: &[u8]) {
+		self.0.direct_egld(to, amount, data)
+	}
+
+	fn direct_esdt_explicit_gas_limit(
+		&self,
+		to: &Address,
+		token: &[u8],
+		amount: &RustBigUint,
+		gas_limit: u64,
+		data: &[u8],
+	) {
+		self.0.direct_esdt_explicit_gas_limit(to, token, amount, gas_limit, data)
+	}
+
+	fn async_call_raw(&self, to: &Address, amount: &RustBigUint, data: &[u8]) -> ! {
+		self.0.async_call_raw(to, amount, data)
+	}
+
+	fn deploy_contract(
+		&self,
+		gas: u64,
+		amount: &RustBigUint,
+		code: &BoxedBytes,
+		code_metadata: CodeMetadata,
+		arg_buffer: &ArgBuffer,
+	) -> Address {
+		self.0.deploy_contract(gas, amount, code, code_metadata, arg_buffer)
+	}
+
+	fn execute_on_dest_context(
+		&self,
+		_gas: u64,
+		address: &Address,
+		value: &RustBigUint,
+		function: &[u8],
+		arg_buffer: &ArgBuffer,
+	) {
+		self.execute_sync_call(address, value, function, arg_buffer)
+	}
+
+	fn execute_on_dest_context_by_caller(
+		&self,
+		gas: u64,
+		address: &Address,
+		value: &RustBigUint,
+		function: &[u8],
+		arg_buffer: &ArgBuffer,
+	) {
+		self.0.execute_on_dest_context_by_caller(gas, address, value, function, arg_buffer)
+	}
+
+	fn execute_on_same_context(
+		&self,
+		gas: u64,
+		address: &Address,
+		value: &RustBigUint,
+		function: &[u8],
+		arg_buffer: &ArgBuffer,
+	) {
+		self.0.execute_on_same_context(gas, address, value, function, arg_buffer)
+	}
+}
+
+impl ErrorApi for TestContext {
+	fn signal_error(&self, message: &[u8]) -> ! {
+		self.0.signal_error(message)
+	}
+}
+
+impl CallValueApi<RustBigUint> for TestContext {
+	fn check_not_payable(&self) {
+		self.0.check_not_payable()
+	}
+
+	fn egld_value(&self) -> RustBigUint {
+		self.0.egld_value()
+	}
+
+	fn esdt_value(&self) -> RustBigUint {
+		self.0.esdt_value()
+	}
+
+	fn token(&self) -> TokenIdentifier {
+		self.0.token()
+	}
+}
+
+impl EndpointArgumentApi for TestContext {
+	fn get_num_arguments(&self) -> i32 {
+		self.0.get_num_arguments()
+	}
+
+	fn get_argument_len(&self, arg_index: i32) -> usize {
+		self.0.get_argument_len(arg_index)
+	}
+
+	fn copy_argument_to_slice(&self, arg_index: i32, slice: &mut [u8]) {
+		self.0.copy_argument_to_slice(arg_index, slice)
+	}
+
+	fn get_argument_vec_u8(&self, arg_index: i32) -> Vec<u8> {
+		self.0.get_argument_vec_u8(arg_index)
+	}
+
+	fn get_argument_big_int_raw(&self, arg_id: i32) -> i32 {
+		self.0.get_argument_big_int_raw(arg_id)
+	}
+
+	fn get_argument_big_uint_raw(&self, arg_id: i32) -> i32 {
+		self.0.get_argument_big_uint_raw(arg_id)
+	}
+
+	fn get_argument_u64(&self, arg_id: i32) -> u64 {
+		self.0.get_argument_u64(arg_id)
+	}
+
+	fn get_argument_i64(&self, arg_id: i32) -> i64 {
+		self.0.get_argument_i64(arg_id)
+	}
+}
+
+impl EndpointFinishApi for TestContext {
+	fn finish_slice_u8(&self, slice: &[u8]) {
+		self.0.finish_slice_u8(slice)
+	}
+
+	fn finish_big_int_raw(&self, handle: i32) {
+		self.0.finish_big_int_raw(handle)
+	}
+
+	fn finish_big_uint_raw(&self, handle: i32) {
+		self.0.finish_big_uint_raw(handle)
+	}
+
+	fn finish_u64(&self, value: u64) {
+		self.0.finish_u64(value)
+	}
+
+	fn finish_i64(&self, value: i64) {
+		self.0.finish_i64(value)
+	}
+}
+
+impl StorageReadApi for TestContext {
+	fn storage_load_len(&self, key: &[u8]) -> usize {
+		self.0.storage_load_len(key)
+	}
+
+	fn storage_load_vec_u8(&self, key: &[u8]) -> Vec<u8> {
+		self.0.storage_load_vec_u8(key)
+	}
+
+	fn storage_load_big_uint_raw(&self, key: &[u8]) -> i32 {
+		self.0.storage_load_big_uint_raw(key)
+	}
+
+	fn storage_load_u64(&self, key: &[u8]) -> u64 {
+		self.0.storage_load_u64(key)
+	}
+
+	fn storage_load_i64(&self, key: &[u8]) -> i64 {
+		self.0.storage_load_i64(key)
+	}
+}
+
+impl StorageWriteApi for TestContext {
+	fn storage_store_slice_u8(&self, key: &[u8], value: &[u8]) {
+		self.0.storage_store_slice_u8(key, value)
+	}
+
+	fn storage_store_big_uint_raw(&self, key: &[u8], handle: i32) {
+		self.0.storage_store_big_uint_raw(key, handle)
+	}
+
+	fn storage_store_u64(&self, key: &[u8], value: u64) {
+		self.0.storage_store_u64(key, value)
+	}
+
+	fn storage_store_i64(&self, key: &[u8], value: i64) {
+		self.0.storage_store_i64(key, value)
+	}
+}
+
+impl LogApi for TestContext {
+	fn write_log(&self, topics: &[[u8; 32]], data: &[u8]) {
+		self.0.write_log(topics, data)
+	}
+}